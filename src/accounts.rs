@@ -0,0 +1,252 @@
+use namada_sdk::types::{address::Address, key::common::PublicKey};
+use tendermint_rpc::endpoint::block_results;
+use tracing::warn;
+
+use crate::{database::IndexedTx, decoder::TxData, error::Error, utils};
+
+// Attribute of the applied transaction events listing the
+// addresses of the accounts the transaction initialized.
+const INITIALIZED_ACCOUNTS_ATTRIBUTE: &str = "initialized_accounts";
+
+/// A change of an account caused by an applied transaction.
+///
+/// Values that are not changed by the transaction are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct AccountChange {
+    pub address: String,
+    /// Either `init`, `update` or `reveal_pk`.
+    pub kind: &'static str,
+    pub vp_code_hash: Option<Vec<u8>>,
+    pub threshold: Option<i32>,
+    pub public_keys: Option<Vec<String>>,
+    pub tx_hash: Vec<u8>,
+}
+
+/// A public key revealed by a `tx_reveal_pk` transaction, along with the
+/// implicit address derived from it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RevealedPublicKey {
+    pub public_key: String,
+    pub address: String,
+    pub tx_hash: Vec<u8>,
+}
+
+/// Returns the changes of the accounts caused by the transactions of a
+/// block, in the order they have been applied.
+///
+/// The address of an initialized account is not part of the transaction,
+/// it is read from the event of the applied transaction.
+pub(crate) fn account_changes(
+    txs: &[IndexedTx],
+    block_results: &block_results::Response,
+) -> Result<Vec<AccountChange>, Error> {
+    let mut changes = vec![];
+
+    for tx in txs {
+        let IndexedTx::Applied { hash, data, .. } = tx else {
+            continue;
+        };
+
+        let tx_hash = hash.clone();
+
+        match data.as_ref() {
+            TxData::InitAccount(init) => {
+                let Some(address) = initialized_account(block_results, hash)? else {
+                    warn!(
+                        "No initialized account found for transaction {}",
+                        hex::encode(hash)
+                    );
+                    continue;
+                };
+
+                changes.push(AccountChange {
+                    address,
+                    kind: "init",
+                    vp_code_hash: Some(init.vp_code_hash.0.to_vec()),
+                    threshold: Some(i32::from(init.threshold)),
+                    public_keys: Some(public_keys(&init.public_keys)),
+                    tx_hash,
+                });
+            }
+            TxData::UpdateAccount(update) => {
+                // an empty list of keys leaves them unchanged
+                changes.push(AccountChange {
+                    address: update.addr.to_string(),
+                    kind: "update",
+                    vp_code_hash: update.vp_code_hash.as_ref().map(|h| h.0.to_vec()),
+                    threshold: update.threshold.map(i32::from),
+                    public_keys: (!update.public_keys.is_empty())
+                        .then(|| public_keys(&update.public_keys)),
+                    tx_hash,
+                });
+            }
+            TxData::RevealPk(pk) => {
+                changes.push(AccountChange {
+                    address: Address::from(pk).to_string(),
+                    kind: "reveal_pk",
+                    vp_code_hash: None,
+                    threshold: None,
+                    public_keys: Some(vec![pk.to_string()]),
+                    tx_hash,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Returns the public keys revealed by the transactions of a block.
+pub(crate) fn revealed_public_keys(txs: &[IndexedTx]) -> Vec<RevealedPublicKey> {
+    let mut keys = vec![];
+
+    for tx in txs {
+        let IndexedTx::Applied { hash, data, .. } = tx else {
+            continue;
+        };
+
+        if let TxData::RevealPk(pk) = data.as_ref() {
+            keys.push(RevealedPublicKey {
+                public_key: pk.to_string(),
+                address: Address::from(pk).to_string(),
+                tx_hash: hash.clone(),
+            });
+        }
+    }
+
+    keys
+}
+
+/// Returns the account initialized by the transaction identified by `hash`,
+/// as listed by the end block event of the transaction.
+fn initialized_account(
+    block_results: &block_results::Response,
+    hash: &[u8],
+) -> Result<Option<String>, Error> {
+    let event = utils::tx_event(block_results, &hex::encode(hash));
+
+    let Some(accounts) = event.and_then(|event| {
+        event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == INITIALIZED_ACCOUNTS_ATTRIBUTE)
+    }) else {
+        return Ok(None);
+    };
+
+    // a json list of addresses
+    let accounts: Vec<String> = serde_json::from_str(&accounts.value)?;
+
+    Ok(accounts.into_iter().next())
+}
+
+fn public_keys(keys: &[PublicKey]) -> Vec<String> {
+    keys.iter().map(PublicKey::to_string).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use namada_sdk::account::{InitAccount, UpdateAccount};
+    use namada_sdk::types::hash::Hash;
+    use tendermint::abci::Event;
+
+    use super::*;
+    use crate::test_utils::{address, block_results_with_events, IMPLICIT_ADDRESS as ACCOUNT};
+
+    // the ed25519 base point
+    const PUBLIC_KEY: &str = "tpknam1qpvxvenxvenxvenxvenxvenxvenxvenxvenxvenxvenxvenxvenxv5rt7eh";
+
+    fn public_key() -> PublicKey {
+        PublicKey::from_str(PUBLIC_KEY).unwrap()
+    }
+
+    fn init_account() -> InitAccount {
+        InitAccount {
+            public_keys: vec![public_key()],
+            vp_code_hash: Hash([1; 32]),
+            threshold: 1,
+        }
+    }
+
+    #[test]
+    fn test_account_changes() {
+        let block_results = block_results_with_events(vec![Event::new(
+            "applied",
+            [
+                ("hash".to_string(), "0A".to_string()),
+                (
+                    INITIALIZED_ACCOUNTS_ATTRIBUTE.to_string(),
+                    format!("[\"{}\"]", ACCOUNT),
+                ),
+            ],
+        )]);
+        let txs = vec![
+            IndexedTx::applied("tx_init_account", &[0x0a], init_account()),
+            // without event the initialized account is unknown
+            IndexedTx::applied("tx_init_account", &[0x0b], init_account()),
+            IndexedTx::applied(
+                "tx_update_account",
+                &[0x0c],
+                UpdateAccount {
+                    addr: address(ACCOUNT),
+                    vp_code_hash: None,
+                    public_keys: vec![],
+                    threshold: Some(2),
+                },
+            ),
+            IndexedTx::applied("tx_reveal_pk", &[0x0d], public_key()),
+        ];
+
+        let implicit = Address::from(&public_key()).to_string();
+
+        assert_eq!(
+            account_changes(&txs, &block_results).unwrap(),
+            vec![
+                AccountChange {
+                    address: ACCOUNT.to_string(),
+                    kind: "init",
+                    vp_code_hash: Some(vec![1; 32]),
+                    threshold: Some(1),
+                    public_keys: Some(vec![PUBLIC_KEY.to_string()]),
+                    tx_hash: vec![0x0a],
+                },
+                AccountChange {
+                    address: ACCOUNT.to_string(),
+                    kind: "update",
+                    vp_code_hash: None,
+                    threshold: Some(2),
+                    public_keys: None,
+                    tx_hash: vec![0x0c],
+                },
+                AccountChange {
+                    address: implicit,
+                    kind: "reveal_pk",
+                    vp_code_hash: None,
+                    threshold: None,
+                    public_keys: Some(vec![PUBLIC_KEY.to_string()]),
+                    tx_hash: vec![0x0d],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_revealed_public_keys() {
+        let txs = vec![
+            IndexedTx::applied("tx_init_account", &[0x0a], init_account()),
+            IndexedTx::applied("tx_reveal_pk", &[0x0d], public_key()),
+        ];
+
+        assert_eq!(
+            revealed_public_keys(&txs),
+            vec![RevealedPublicKey {
+                public_key: PUBLIC_KEY.to_string(),
+                address: Address::from(&public_key()).to_string(),
+                tx_hash: vec![0x0d],
+            }]
+        );
+    }
+}
@@ -0,0 +1,1002 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use namada_sdk::{
+    tx::data::WrapperTx,
+    types::{
+        address::{Address, InternalAddress},
+        token,
+    },
+};
+use serde_json::Value;
+use tendermint_rpc::endpoint::block_results;
+
+use crate::{
+    bridge_pool::{self, BridgePoolTransfer},
+    database::IndexedTx,
+    decoder::TxData,
+    error::Error,
+    rewards,
+};
+
+// The ICS20 events of the transfers sent, received and refunded after a
+// timeout.
+const IBC_TRANSFER_EVENT: &str = "ibc_transfer";
+const IBC_RECEIVE_EVENT: &str = "fungible_token_packet";
+const IBC_TIMEOUT_EVENT: &str = "timeout";
+
+/// A change of the balance of `address`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BalanceChange {
+    pub address: String,
+    /// The token address, `None` stands for the native token.
+    pub token: Option<String>,
+    /// Signed amount in the token smallest unit.
+    pub delta: String,
+    /// What caused the change, e.g. `transfer` or `fee`.
+    pub kind: &'static str,
+    /// The transaction causing the change, `None` for the genesis balances
+    /// and the changes made by the ledger itself, like ending a proposal.
+    pub tx_hash: Option<Vec<u8>>,
+}
+
+/// A withdraw, whose amount is the one of the unbonds it withdrew, see
+/// [`BlockBalanceChanges::withdrawal_changes`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Withdrawal {
+    pub owner: String,
+    pub tx_hash: Vec<u8>,
+}
+
+/// The balance changes caused by the transactions of a block.
+#[derive(Debug, Default)]
+pub(crate) struct BlockBalanceChanges {
+    pub changes: Vec<BalanceChange>,
+    pub withdrawals: Vec<Withdrawal>,
+}
+
+impl BlockBalanceChanges {
+    /// Returns the balance changes caused by the transactions of a block,
+    /// whose events are in `block_results`, along with the IBC transfers of
+    /// the block.
+    ///
+    /// `min_proposal_fund` is the deposit of the proposals, it fails if the
+    /// block has proposals and it is unknown.
+    pub fn from_txs(
+        txs: &[IndexedTx],
+        block_results: &block_results::Response,
+        min_proposal_fund: Option<&str>,
+    ) -> Result<Self, Error> {
+        let mut changes = Self::default();
+
+        for tx in txs {
+            match tx {
+                IndexedTx::Wrapper { hash, wrapper } => changes.add_fee(wrapper, hash)?,
+                IndexedTx::Applied { hash, data, .. } => {
+                    changes.add_tx(data, hash, block_results, min_proposal_fund)?
+                }
+                IndexedTx::Decrypted { .. } => {}
+            }
+        }
+
+        changes.changes.extend(ibc_changes(block_results));
+
+        Ok(changes)
+    }
+
+    /// Add the fee paid by the signer of a wrapper transaction.
+    ///
+    /// Fees are credited to the block proposer, which is only known by its
+    /// consensus address here, so only the payer side is recorded.
+    fn add_fee(&mut self, wrapper: &WrapperTx, tx_hash: &[u8]) -> Result<(), Error> {
+        let fee = wrapper
+            .get_tx_fee()
+            .map_err(|e| Error::InvalidTxData(e.to_string()))?;
+
+        self.changes.push(BalanceChange {
+            address: wrapper.fee_payer().to_string(),
+            token: Some(wrapper.fee.token.to_string()),
+            delta: negative(fee.amount()),
+            kind: "fee",
+            tx_hash: Some(tx_hash.to_vec()),
+        });
+
+        Ok(())
+    }
+
+    /// Add the changes caused by an applied transaction.
+    ///
+    /// Unbonds don't move any token, the unbonded amount becomes part
+    /// of the balance once it is withdrawn. The amount of a reward claim is
+    /// read from its event, there is no change if it is unknown. Shielding
+    /// and unshielding transfers move tokens from and to the MASP address.
+    fn add_tx(
+        &mut self,
+        data: &TxData,
+        tx_hash: &[u8],
+        block_results: &block_results::Response,
+        min_proposal_fund: Option<&str>,
+    ) -> Result<(), Error> {
+        match data {
+            TxData::Transfer(transfer) => {
+                let token = Some(transfer.token.to_string());
+                let amount = transfer.amount.amount();
+
+                self.push(
+                    &transfer.source,
+                    token.clone(),
+                    negative(amount),
+                    "transfer",
+                    tx_hash,
+                );
+                self.push(
+                    &transfer.target,
+                    token,
+                    positive(amount),
+                    "transfer",
+                    tx_hash,
+                );
+            }
+            TxData::Bond(bond) => {
+                let source = bond.source.as_ref().unwrap_or(&bond.validator);
+
+                self.push(source, None, negative(bond.amount), "bond", tx_hash);
+                self.push(&pos(), None, positive(bond.amount), "bond", tx_hash);
+            }
+            TxData::Withdraw(withdraw) => {
+                self.withdrawals.push(Withdrawal {
+                    owner: withdraw
+                        .source
+                        .as_ref()
+                        .unwrap_or(&withdraw.validator)
+                        .to_string(),
+                    tx_hash: tx_hash.to_vec(),
+                });
+            }
+            TxData::ClaimRewards(claim) => {
+                let Some(amount) = rewards::claimed_amount(block_results, tx_hash) else {
+                    return Ok(());
+                };
+                let owner = claim.source.as_ref().unwrap_or(&claim.validator);
+
+                self.push(owner, None, amount.clone(), "claim_rewards", tx_hash);
+                self.push(
+                    &pos(),
+                    None,
+                    format!("-{}", amount),
+                    "claim_rewards",
+                    tx_hash,
+                );
+            }
+            TxData::InitProposal(proposal) => {
+                let deposit = min_proposal_fund
+                    .ok_or_else(|| Error::MissingBlockState("min proposal fund".into()))?;
+
+                self.push(
+                    &proposal.author,
+                    None,
+                    format!("-{}", deposit),
+                    "proposal_deposit",
+                    tx_hash,
+                );
+                self.push(
+                    &governance(),
+                    None,
+                    deposit.to_string(),
+                    "proposal_deposit",
+                    tx_hash,
+                );
+            }
+            TxData::BridgePool(pending) => {
+                let transfer = bridge_pool::bridge_pool_transfer(pending, tx_hash);
+                let pool = Address::Internal(InternalAddress::EthBridgePool).to_string();
+
+                // the bridge pool holds the amount and the gas fee until the
+                // transfer is relayed or expires.
+                for (address, token, amount) in [
+                    (&transfer.sender, &transfer.token, &transfer.amount),
+                    (
+                        &transfer.gas_fee_payer,
+                        &transfer.gas_fee_token,
+                        &transfer.gas_fee,
+                    ),
+                ] {
+                    self.changes.extend(moved(
+                        address,
+                        &pool,
+                        Some(token.as_str()),
+                        amount,
+                        "bridge_pool",
+                        Some(tx_hash),
+                    ));
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn push(
+        &mut self,
+        address: &Address,
+        token: Option<String>,
+        delta: String,
+        kind: &'static str,
+        tx_hash: &[u8],
+    ) {
+        self.changes.push(BalanceChange {
+            address: address.to_string(),
+            token,
+            delta,
+            kind,
+            tx_hash: Some(tx_hash.to_vec()),
+        });
+    }
+
+    /// Returns the balance changes of the withdrawals of the block, both for
+    /// their owner and for the PoS address.
+    ///
+    /// A withdraw receives the amount of the unbonds it withdrew, by
+    /// transaction hash in `unbonded`, there is no change for the withdraws
+    /// that did not withdraw any unbond. The withdrawn unbonds are only known
+    /// with the `epoch` of the block, withdraws fail without it.
+    pub fn withdrawal_changes(
+        &self,
+        epoch: Option<u64>,
+        unbonded: &HashMap<Vec<u8>, String>,
+    ) -> Result<Vec<BalanceChange>, Error> {
+        if !self.withdrawals.is_empty() && epoch.is_none() {
+            return Err(Error::MissingBlockState("epoch of the withdraws".into()));
+        }
+
+        let mut changes = vec![];
+
+        for withdrawal in &self.withdrawals {
+            let Some(amount) = unbonded.get(&withdrawal.tx_hash) else {
+                continue;
+            };
+
+            changes.push(BalanceChange {
+                address: withdrawal.owner.clone(),
+                token: None,
+                delta: amount.clone(),
+                kind: "withdraw",
+                tx_hash: Some(withdrawal.tx_hash.clone()),
+            });
+            changes.push(BalanceChange {
+                address: pos().to_string(),
+                token: None,
+                delta: format!("-{}", amount),
+                kind: "withdraw",
+                tx_hash: Some(withdrawal.tx_hash.clone()),
+            });
+        }
+
+        Ok(changes)
+    }
+}
+
+/// Returns the balance changes of the IBC transfers of a block, read from
+/// the ICS20 events of the transfers sent, received and refunded after a
+/// timeout. The tokens leave and enter the chain through the IBC address.
+///
+/// Tokens coming back through IBC are identified by the address ending
+/// their denomination, the other ones by their denomination. Events with
+/// missing attributes or an invalid amount are ignored.
+pub(crate) fn ibc_changes(block_results: &block_results::Response) -> Vec<BalanceChange> {
+    let events = block_results
+        .begin_block_events
+        .iter()
+        .flatten()
+        .chain(block_results.end_block_events.iter().flatten())
+        .chain(block_results.finalize_block_events.iter());
+
+    let ibc = Address::Internal(InternalAddress::Ibc).to_string();
+    let mut changes = vec![];
+
+    for event in events {
+        let attribute = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|attr| attr.key == key)
+                .map(|attr| attr.value.as_str())
+        };
+
+        let (owner, denom, amount, sent) = match event.kind.as_str() {
+            IBC_TRANSFER_EVENT => (
+                attribute("sender"),
+                attribute("denom"),
+                attribute("amount"),
+                true,
+            ),
+            IBC_RECEIVE_EVENT if attribute("success") == Some("true") => (
+                attribute("receiver"),
+                attribute("denom"),
+                attribute("amount"),
+                false,
+            ),
+            IBC_TIMEOUT_EVENT => (
+                attribute("refund_receiver"),
+                attribute("refund_denom"),
+                attribute("refund_amount"),
+                false,
+            ),
+            _ => continue,
+        };
+
+        let (Some(owner), Some(denom), Some(amount)) = (owner, denom, amount) else {
+            continue;
+        };
+
+        if amount.parse::<u128>().is_err() {
+            continue;
+        }
+
+        let token = ibc_token(denom);
+        let tx_hash = attribute("hash").and_then(|hash| hex::decode(hash).ok());
+        let (from, to) = if sent {
+            (owner, ibc.as_str())
+        } else {
+            (ibc.as_str(), owner)
+        };
+
+        changes.extend(moved(
+            from,
+            to,
+            Some(token.as_str()),
+            amount,
+            "ibc",
+            tx_hash.as_deref(),
+        ));
+    }
+
+    changes
+}
+
+/// Returns the balance changes of a pending transfer of the bridge pool
+/// whose status changed: an `expired` transfer is refunded and the amount of
+/// a `relayed` one leaves the chain.
+///
+/// The gas fee of a relayed transfer is paid to its relayer, which is not
+/// part of the events, so it is left in the bridge pool.
+pub(crate) fn bridge_pool_status_changes(
+    transfer: &BridgePoolTransfer,
+    status: &str,
+) -> Vec<BalanceChange> {
+    let pool = Address::Internal(InternalAddress::EthBridgePool).to_string();
+
+    match status {
+        "expired" => {
+            let mut changes = moved(
+                &pool,
+                &transfer.sender,
+                Some(transfer.token.as_str()),
+                &transfer.amount,
+                "bridge_pool_refund",
+                None,
+            );
+            changes.extend(moved(
+                &pool,
+                &transfer.gas_fee_payer,
+                Some(transfer.gas_fee_token.as_str()),
+                &transfer.gas_fee,
+                "bridge_pool_refund",
+                None,
+            ));
+            changes
+        }
+        "relayed" => vec![BalanceChange {
+            address: pool,
+            token: Some(transfer.token.clone()),
+            delta: format!("-{}", transfer.amount),
+            kind: "bridge_pool_relay",
+            tx_hash: None,
+        }],
+        _ => vec![],
+    }
+}
+
+/// Returns the balance changes of the end of a proposal, whose `deposit` is
+/// given back to its `author` if it passed and goes to PGF otherwise.
+pub(crate) fn proposal_result_changes(
+    author: &str,
+    deposit: &str,
+    passed: bool,
+) -> Vec<BalanceChange> {
+    let governance = governance().to_string();
+
+    if passed {
+        moved(&governance, author, None, deposit, "proposal_refund", None)
+    } else {
+        moved(
+            &governance,
+            &pgf().to_string(),
+            None,
+            deposit,
+            "proposal_forfeit",
+            None,
+        )
+    }
+}
+
+/// Returns the balance changes of the retro payments of a passed
+/// `PGFPayment` proposal, from the type data stored along with the proposal,
+/// e.g. `{ "PGFPayment": [{ "Retro": { "Internal": { "target": "tnam1...",
+/// "amount": "100" } } }] }`.
+///
+/// Payments to other chains leave through the IBC address. The continuous
+/// payments are made by the ledger at every epoch, without any event, and
+/// are not recorded.
+pub(crate) fn pgf_payment_changes(kind_data: &Value) -> Vec<BalanceChange> {
+    let Some(actions) = kind_data.get("PGFPayment").and_then(Value::as_array) else {
+        return vec![];
+    };
+
+    let pgf = pgf().to_string();
+    let ibc = Address::Internal(InternalAddress::Ibc).to_string();
+    let mut changes = vec![];
+
+    for retro in actions.iter().filter_map(|action| action.get("Retro")) {
+        let (target, payment) = match (retro.get("Internal"), retro.get("Ibc")) {
+            (Some(internal), _) => (internal.get("target").and_then(Value::as_str), internal),
+            (_, Some(ibc_target)) => (Some(ibc.as_str()), ibc_target),
+            _ => (retro.get("target").and_then(Value::as_str), retro),
+        };
+
+        let amount = match payment.get("amount") {
+            Some(Value::String(amount)) => amount.clone(),
+            Some(Value::Number(amount)) => amount.to_string(),
+            _ => continue,
+        };
+
+        let Some(target) = target else {
+            continue;
+        };
+
+        changes.extend(moved(&pgf, target, None, &amount, "pgf_payment", None));
+    }
+
+    changes
+}
+
+/// Returns the genesis balances, what is left of the `initial_balances`,
+/// once the first block has been committed, when the `changes` of the block
+/// are taken out. Changes without token are in `native_token`, the ones of
+/// tokens without initial balance are left out.
+pub(crate) fn genesis_changes(
+    initial_balances: &[(String, String, String)],
+    changes: &[BalanceChange],
+    native_token: &str,
+) -> Result<Vec<BalanceChange>, Error> {
+    let mut balances: BTreeMap<(&str, &str), i128> = BTreeMap::new();
+
+    for (owner, token, amount) in initial_balances {
+        *balances
+            .entry((owner.as_str(), token.as_str()))
+            .or_default() += amount.parse::<i128>()?;
+    }
+
+    let tokens: HashSet<&str> = initial_balances
+        .iter()
+        .map(|(_, token, _)| token.as_str())
+        .collect();
+
+    for change in changes {
+        let token = change.token.as_deref().unwrap_or(native_token);
+
+        if tokens.contains(token) {
+            *balances
+                .entry((change.address.as_str(), token))
+                .or_default() -= change.delta.parse::<i128>()?;
+        }
+    }
+
+    Ok(balances
+        .into_iter()
+        .filter(|(_, amount)| *amount != 0)
+        .map(|((address, token), amount)| BalanceChange {
+            address: address.to_string(),
+            token: Some(token.to_string()),
+            delta: amount.to_string(),
+            kind: "genesis",
+            tx_hash: None,
+        })
+        .collect())
+}
+
+/// The proof of stake address, where bonded tokens are held.
+pub(crate) fn pos() -> Address {
+    Address::Internal(InternalAddress::PoS)
+}
+
+/// The governance address, where the proposal deposits are held.
+fn governance() -> Address {
+    Address::Internal(InternalAddress::Governance)
+}
+
+/// The PGF address, paying the public goods.
+fn pgf() -> Address {
+    Address::Internal(InternalAddress::Pgf)
+}
+
+// Returns the changes of `amount` of `token` moving from `from` to `to`, the
+// native token if `None`.
+fn moved(
+    from: &str,
+    to: &str,
+    token: Option<&str>,
+    amount: &str,
+    kind: &'static str,
+    tx_hash: Option<&[u8]>,
+) -> Vec<BalanceChange> {
+    vec![
+        BalanceChange {
+            address: from.to_string(),
+            token: token.map(str::to_string),
+            delta: format!("-{}", amount),
+            kind,
+            tx_hash: tx_hash.map(|hash| hash.to_vec()),
+        },
+        BalanceChange {
+            address: to.to_string(),
+            token: token.map(str::to_string),
+            delta: amount.to_string(),
+            kind,
+            tx_hash: tx_hash.map(|hash| hash.to_vec()),
+        },
+    ]
+}
+
+// Tokens coming back through IBC end their denomination with their address,
+// like `transfer/channel-0/tnam1...`.
+fn ibc_token(denom: &str) -> String {
+    let base = denom.rsplit('/').next().unwrap_or(denom);
+
+    match Address::decode(base) {
+        Ok(address) => address.to_string(),
+        Err(_) => denom.to_string(),
+    }
+}
+
+fn positive(amount: token::Amount) -> String {
+    amount.raw_amount().to_string()
+}
+
+fn negative(amount: token::Amount) -> String {
+    format!("-{}", amount.raw_amount())
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_sdk::governance::{storage::proposal::ProposalType, InitProposalData};
+    use namada_sdk::tx::data::pos::{Bond, Withdraw};
+    use namada_sdk::types::{
+        eth_bridge_pool::{GasFee, PendingTransfer, TransferToEthereum, TransferToEthereumKind},
+        ethereum_events::EthAddress,
+        hash::Hash,
+        storage::Epoch,
+        token::{DenominatedAmount, Denomination, Transfer},
+    };
+    use serde_json::json;
+    use tendermint::abci::Event;
+
+    use super::*;
+    use crate::rewards::CLAIMED_REWARDS_ATTRIBUTE;
+    use crate::test_utils::{
+        address, block_results_with_events, ADDRESS as OWNER, NATIVE, OTHER_ADDRESS as VALIDATOR,
+        THIRD_ADDRESS as TARGET,
+    };
+
+    fn transfer(amount: u64) -> Transfer {
+        Transfer {
+            source: address(OWNER),
+            target: address(TARGET),
+            token: address(NATIVE),
+            amount: DenominatedAmount::new(token::Amount::from_u64(amount), Denomination(0)),
+            key: None,
+            shielded: None,
+        }
+    }
+
+    fn withdraw() -> Withdraw {
+        Withdraw {
+            validator: address(VALIDATOR),
+            source: Some(address(OWNER)),
+        }
+    }
+
+    fn pending() -> PendingTransfer {
+        PendingTransfer {
+            transfer: TransferToEthereum {
+                kind: TransferToEthereumKind::Erc20,
+                asset: EthAddress([1; 20]),
+                recipient: EthAddress([2; 20]),
+                sender: address(OWNER),
+                amount: token::Amount::from_u64(100),
+            },
+            gas_fee: GasFee {
+                amount: token::Amount::from_u64(5),
+                payer: address(TARGET),
+                token: address(NATIVE),
+            },
+        }
+    }
+
+    fn deltas(changes: &[BalanceChange]) -> Vec<(&str, &str, &str)> {
+        changes
+            .iter()
+            .map(|c| (c.address.as_str(), c.kind, c.delta.as_str()))
+            .collect()
+    }
+
+    fn claimed(hash: &str, amount: &str) -> Event {
+        Event::new(
+            "applied",
+            [
+                ("hash".to_string(), hash.to_string()),
+                (CLAIMED_REWARDS_ATTRIBUTE.to_string(), amount.to_string()),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_from_txs() {
+        let txs = vec![
+            IndexedTx::applied("tx_transfer", &[1], transfer(50)),
+            IndexedTx::applied(
+                "tx_bond",
+                &[2],
+                Bond {
+                    validator: address(VALIDATOR),
+                    amount: token::Amount::from_u64(20),
+                    source: None,
+                },
+            ),
+            IndexedTx::applied("tx_withdraw", &[3], withdraw()),
+            IndexedTx::applied("tx_claim_rewards", &[4], withdraw()),
+            IndexedTx::Decrypted {
+                fee_token: None,
+                gas_used: None,
+            },
+        ];
+        let block_results = block_results_with_events(vec![claimed("04", "30")]);
+
+        let changes = BlockBalanceChanges::from_txs(&txs, &block_results, None).unwrap();
+        let pos = pos().to_string();
+
+        assert_eq!(
+            deltas(&changes.changes),
+            vec![
+                (OWNER, "transfer", "-50"),
+                (TARGET, "transfer", "50"),
+                (VALIDATOR, "bond", "-20"),
+                (pos.as_str(), "bond", "20"),
+                (OWNER, "claim_rewards", "30"),
+                (pos.as_str(), "claim_rewards", "-30"),
+            ]
+        );
+        assert_eq!(changes.changes[0].token.as_deref(), Some(NATIVE));
+        assert_eq!(changes.changes[2].token, None);
+        assert_eq!(changes.changes[4].tx_hash, Some(vec![4]));
+
+        assert_eq!(
+            changes.withdrawals,
+            vec![Withdrawal {
+                owner: OWNER.to_string(),
+                tx_hash: vec![3],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_from_txs_claims() {
+        // every claim of a block gets the amount of its own event
+        let txs = vec![
+            IndexedTx::applied("tx_claim_rewards", &[1], withdraw()),
+            IndexedTx::applied("tx_claim_rewards", &[2], withdraw()),
+            // without event the amount is unknown
+            IndexedTx::applied("tx_claim_rewards", &[3], withdraw()),
+        ];
+        let block_results =
+            block_results_with_events(vec![claimed("01", "30"), claimed("02", "12")]);
+
+        let changes = BlockBalanceChanges::from_txs(&txs, &block_results, None).unwrap();
+        let pos = pos().to_string();
+
+        assert_eq!(
+            deltas(&changes.changes),
+            vec![
+                (OWNER, "claim_rewards", "30"),
+                (pos.as_str(), "claim_rewards", "-30"),
+                (OWNER, "claim_rewards", "12"),
+                (pos.as_str(), "claim_rewards", "-12"),
+            ]
+        );
+        assert_eq!(changes.changes[2].tx_hash, Some(vec![2]));
+        assert!(changes.withdrawals.is_empty());
+    }
+
+    #[test]
+    fn test_from_txs_proposal() {
+        let txs = vec![IndexedTx::applied(
+            "tx_init_proposal",
+            &[1],
+            InitProposalData {
+                id: 0,
+                content: Hash::default(),
+                author: address(OWNER),
+                r#type: ProposalType::Default(None),
+                voting_start_epoch: Epoch(1),
+                voting_end_epoch: Epoch(4),
+                grace_epoch: Epoch(6),
+            },
+        )];
+        let block_results = block_results_with_events(vec![]);
+
+        let changes = BlockBalanceChanges::from_txs(&txs, &block_results, Some("500")).unwrap();
+        let governance = governance().to_string();
+
+        assert_eq!(
+            deltas(&changes.changes),
+            vec![
+                (OWNER, "proposal_deposit", "-500"),
+                (governance.as_str(), "proposal_deposit", "500"),
+            ]
+        );
+        assert_eq!(changes.changes[0].token, None);
+
+        assert!(matches!(
+            BlockBalanceChanges::from_txs(&txs, &block_results, None),
+            Err(Error::MissingBlockState(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_txs_bridge_pool() {
+        let txs = vec![IndexedTx::applied("tx_bridge_pool", &[1], pending())];
+        let block_results = block_results_with_events(vec![]);
+
+        let changes = BlockBalanceChanges::from_txs(&txs, &block_results, None).unwrap();
+        let pool = Address::Internal(InternalAddress::EthBridgePool).to_string();
+        let erc20 = Address::Internal(InternalAddress::Erc20(EthAddress([1; 20]))).to_string();
+
+        assert_eq!(
+            deltas(&changes.changes),
+            vec![
+                (OWNER, "bridge_pool", "-100"),
+                (pool.as_str(), "bridge_pool", "100"),
+                (TARGET, "bridge_pool", "-5"),
+                (pool.as_str(), "bridge_pool", "5"),
+            ]
+        );
+        assert_eq!(changes.changes[0].token, Some(erc20));
+        assert_eq!(changes.changes[2].token.as_deref(), Some(NATIVE));
+    }
+
+    #[test]
+    fn test_withdrawal_changes() {
+        let txs = vec![
+            IndexedTx::applied("tx_withdraw", &[2], withdraw()),
+            IndexedTx::applied("tx_withdraw", &[3], withdraw()),
+            // nothing to withdraw
+            IndexedTx::applied("tx_withdraw", &[4], withdraw()),
+        ];
+        let block_results = block_results_with_events(vec![]);
+        let changes = BlockBalanceChanges::from_txs(&txs, &block_results, None).unwrap();
+
+        let unbonded = HashMap::from([(vec![2], "100".to_string()), (vec![3], "40".to_string())]);
+
+        let withdrawals = changes.withdrawal_changes(Some(5), &unbonded).unwrap();
+        let pos = pos().to_string();
+
+        assert_eq!(
+            deltas(&withdrawals),
+            vec![
+                (OWNER, "withdraw", "100"),
+                (pos.as_str(), "withdraw", "-100"),
+                (OWNER, "withdraw", "40"),
+                (pos.as_str(), "withdraw", "-40"),
+            ]
+        );
+        assert_eq!(withdrawals[2].tx_hash, Some(vec![3]));
+    }
+
+    #[test]
+    fn test_withdrawal_changes_unknown_epoch() {
+        let txs = vec![IndexedTx::applied("tx_withdraw", &[2], withdraw())];
+        let block_results = block_results_with_events(vec![]);
+        let changes = BlockBalanceChanges::from_txs(&txs, &block_results, None).unwrap();
+
+        // the withdrawn unbonds can't be told without the epoch
+        assert!(matches!(
+            changes.withdrawal_changes(None, &HashMap::new()),
+            Err(Error::MissingBlockState(_))
+        ));
+
+        // nor are they needed without withdraws
+        let changes = BlockBalanceChanges::from_txs(&[], &block_results, None).unwrap();
+
+        assert!(changes
+            .withdrawal_changes(None, &HashMap::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_ibc_changes() {
+        let ibc = Address::Internal(InternalAddress::Ibc).to_string();
+        let returned = format!("transfer/channel-0/{}", NATIVE);
+        let block_results = block_results_with_events(vec![
+            Event::new(
+                "ibc_transfer",
+                [
+                    ("sender", OWNER),
+                    ("receiver", "cosmos1receiver"),
+                    ("denom", NATIVE),
+                    ("amount", "10"),
+                    ("hash", "0A"),
+                ],
+            ),
+            Event::new(
+                "fungible_token_packet",
+                [
+                    ("sender", "cosmos1sender"),
+                    ("receiver", TARGET),
+                    ("denom", "uatom"),
+                    ("amount", "7"),
+                    ("success", "true"),
+                ],
+            ),
+            Event::new(
+                "timeout",
+                [
+                    ("refund_receiver", OWNER),
+                    ("refund_denom", returned.as_str()),
+                    ("refund_amount", "3"),
+                ],
+            ),
+            // failed and acknowledged packets don't move any token
+            Event::new(
+                "fungible_token_packet",
+                [
+                    ("receiver", TARGET),
+                    ("denom", "uatom"),
+                    ("amount", "7"),
+                    ("success", "false"),
+                ],
+            ),
+            Event::new("fungible_token_packet", [("success", "true")]),
+        ]);
+
+        let changes = ibc_changes(&block_results);
+
+        assert_eq!(
+            deltas(&changes),
+            vec![
+                (OWNER, "ibc", "-10"),
+                (ibc.as_str(), "ibc", "10"),
+                (ibc.as_str(), "ibc", "-7"),
+                (TARGET, "ibc", "7"),
+                (ibc.as_str(), "ibc", "-3"),
+                (OWNER, "ibc", "3"),
+            ]
+        );
+        assert_eq!(changes[0].tx_hash, Some(vec![10]));
+        assert_eq!(changes[0].token.as_deref(), Some(NATIVE));
+        assert_eq!(changes[2].token.as_deref(), Some("uatom"));
+        assert_eq!(changes[2].tx_hash, None);
+        assert_eq!(changes[4].token.as_deref(), Some(NATIVE));
+    }
+
+    #[test]
+    fn test_bridge_pool_status_changes() {
+        let transfer = bridge_pool::bridge_pool_transfer(&pending(), &[1]);
+        let pool = Address::Internal(InternalAddress::EthBridgePool).to_string();
+
+        assert_eq!(
+            deltas(&bridge_pool_status_changes(&transfer, "expired")),
+            vec![
+                (pool.as_str(), "bridge_pool_refund", "-100"),
+                (OWNER, "bridge_pool_refund", "100"),
+                (pool.as_str(), "bridge_pool_refund", "-5"),
+                (TARGET, "bridge_pool_refund", "5"),
+            ]
+        );
+        assert_eq!(
+            deltas(&bridge_pool_status_changes(&transfer, "relayed")),
+            vec![(pool.as_str(), "bridge_pool_relay", "-100")]
+        );
+        assert!(bridge_pool_status_changes(&transfer, "pending").is_empty());
+    }
+
+    #[test]
+    fn test_proposal_result_changes() {
+        let governance = governance().to_string();
+        let pgf = pgf().to_string();
+
+        assert_eq!(
+            deltas(&proposal_result_changes(OWNER, "500", true)),
+            vec![
+                (governance.as_str(), "proposal_refund", "-500"),
+                (OWNER, "proposal_refund", "500"),
+            ]
+        );
+        assert_eq!(
+            deltas(&proposal_result_changes(OWNER, "500", false)),
+            vec![
+                (governance.as_str(), "proposal_forfeit", "-500"),
+                (pgf.as_str(), "proposal_forfeit", "500"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pgf_payment_changes() {
+        let kind_data = json!({
+            "PGFPayment": [
+                { "Retro": { "Internal": { "target": TARGET, "amount": "100" } } },
+                { "Retro": { "Ibc": { "target": "cosmos1target", "amount": "20" } } },
+                { "Continuous": { "Add": { "Internal": { "target": OWNER, "amount": "5" } } } },
+            ]
+        });
+
+        let changes = pgf_payment_changes(&kind_data);
+        let pgf = pgf().to_string();
+        let ibc = Address::Internal(InternalAddress::Ibc).to_string();
+
+        assert_eq!(
+            deltas(&changes),
+            vec![
+                (pgf.as_str(), "pgf_payment", "-100"),
+                (TARGET, "pgf_payment", "100"),
+                (pgf.as_str(), "pgf_payment", "-20"),
+                (ibc.as_str(), "pgf_payment", "20"),
+            ]
+        );
+        assert_eq!(changes[0].token, None);
+
+        assert!(pgf_payment_changes(&json!("Default")).is_empty());
+    }
+
+    #[test]
+    fn test_genesis_changes() {
+        let initial_balances = vec![
+            (OWNER.to_string(), NATIVE.to_string(), "1000".to_string()),
+            (TARGET.to_string(), NATIVE.to_string(), "250".to_string()),
+        ];
+        let changes = vec![
+            BalanceChange {
+                address: OWNER.to_string(),
+                token: None,
+                delta: "-50".to_string(),
+                kind: "transfer",
+                tx_hash: Some(vec![1]),
+            },
+            BalanceChange {
+                address: TARGET.to_string(),
+                token: Some(NATIVE.to_string()),
+                delta: "50".to_string(),
+                kind: "transfer",
+                tx_hash: Some(vec![1]),
+            },
+            // not read from the node
+            BalanceChange {
+                address: OWNER.to_string(),
+                token: Some("tnam1other".to_string()),
+                delta: "8".to_string(),
+                kind: "transfer",
+                tx_hash: Some(vec![2]),
+            },
+        ];
+
+        let genesis = genesis_changes(&initial_balances, &changes, NATIVE).unwrap();
+
+        assert_eq!(
+            deltas(&genesis),
+            vec![(OWNER, "genesis", "1050"), (TARGET, "genesis", "200")]
+        );
+        assert_eq!(genesis[0].token.as_deref(), Some(NATIVE));
+        assert_eq!(genesis[0].tx_hash, None);
+    }
+}
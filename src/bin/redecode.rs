@@ -0,0 +1,51 @@
+use clap::Parser;
+use namadexer::setup_logging;
+use namadexer::CliSettings;
+use namadexer::Database;
+use namadexer::Error;
+use namadexer::Settings;
+
+use tracing::info;
+
+/// Re-runs the transaction decoders over the raw transactions stored
+/// by the indexer (see the `store_raw_txs` database setting).
+#[derive(Debug, Parser)]
+struct RedecodeArgs {
+    /// First block height to redecode
+    #[clap(long)]
+    from_height: Option<u32>,
+    /// Last block height to redecode (included)
+    #[clap(long)]
+    to_height: Option<u32>,
+    /// Only redecode transactions of this type (e.g. tx_transfer)
+    #[clap(long)]
+    code_type: Option<String>,
+    #[clap(flatten)]
+    settings: CliSettings,
+}
+
+#[tokio::main(flavor = "multi_thread", worker_threads = 4)]
+async fn main() -> Result<(), Error> {
+    let args = RedecodeArgs::parse();
+
+    // The configuration file has priority over the command line
+    // settings, same as for the indexer.
+    let cfg = match Settings::from_config_file()? {
+        Some(cfg) => cfg,
+        None => Settings::from(args.settings),
+    };
+
+    setup_logging(&cfg);
+
+    info!("Starting database connection");
+
+    let db = Database::new(cfg.database_config(), cfg.chain_name.as_str()).await?;
+
+    let updated = db
+        .redecode(args.from_height, args.to_height, args.code_type.as_deref())
+        .await?;
+
+    info!("{} transactions redecoded", updated);
+
+    Ok(())
+}
@@ -0,0 +1,262 @@
+use namada_sdk::types::{
+    address::{Address, InternalAddress},
+    eth_abi::Encode,
+    eth_bridge_pool::{PendingTransfer, TransferToEthereumKind},
+};
+use tendermint_rpc::endpoint::block_results;
+
+use crate::{database::IndexedTx, decoder::TxData};
+
+// The ledger events are matched by the end of their type, like the
+// slashes, and the bridge pool ones by their `kind` attribute.
+const ETHEREUM_BRIDGE_EVENT: &str = "ethereum_bridge";
+const BRIDGE_POOL_KIND: &str = "bridge_pool";
+
+/// A transfer to Ethereum added to the bridge pool by `tx_bridge_pool`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BridgePoolTransfer {
+    /// Keccak hash of the pending transfer, used by the bridge events.
+    pub transfer_hash: Vec<u8>,
+    /// Either `erc20` or `nut`.
+    pub kind: String,
+    /// Address of the Ethereum asset.
+    pub asset: String,
+    /// Address of the Namada token wrapping the asset.
+    pub token: String,
+    /// Ethereum address receiving the asset.
+    pub recipient: String,
+    pub sender: String,
+    /// Amounts in the token smallest unit.
+    pub amount: String,
+    pub gas_fee: String,
+    pub gas_fee_token: String,
+    pub gas_fee_payer: String,
+    pub tx_hash: Vec<u8>,
+}
+
+/// A change of the status of pending transfers, either `relayed` or
+/// `expired`, reported by the Ethereum bridge events.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BridgePoolStatus {
+    pub transfer_hash: Vec<u8>,
+    pub status: String,
+}
+
+/// Returns the transfers added to the bridge pool by the transactions of a
+/// block.
+pub(crate) fn bridge_pool_transfers(txs: &[IndexedTx]) -> Vec<BridgePoolTransfer> {
+    let mut transfers = vec![];
+
+    for tx in txs {
+        let IndexedTx::Applied { hash, data, .. } = tx else {
+            continue;
+        };
+
+        let TxData::BridgePool(pending) = data.as_ref() else {
+            continue;
+        };
+
+        transfers.push(bridge_pool_transfer(pending, hash));
+    }
+
+    transfers
+}
+
+/// Returns the transfer added to the bridge pool by the transaction `tx_hash`.
+pub(crate) fn bridge_pool_transfer(
+    pending: &PendingTransfer,
+    tx_hash: &[u8],
+) -> BridgePoolTransfer {
+    let asset = pending.transfer.asset;
+    let token = match pending.transfer.kind {
+        TransferToEthereumKind::Erc20 => InternalAddress::Erc20(asset),
+        TransferToEthereumKind::Nut => InternalAddress::Nut(asset),
+    };
+
+    BridgePoolTransfer {
+        transfer_hash: pending.keccak256().0.to_vec(),
+        kind: format!("{:?}", pending.transfer.kind).to_ascii_lowercase(),
+        asset: asset.to_string(),
+        token: Address::Internal(token).to_string(),
+        recipient: pending.transfer.recipient.to_string(),
+        sender: pending.transfer.sender.to_string(),
+        amount: pending.transfer.amount.raw_amount().to_string(),
+        gas_fee: pending.gas_fee.amount.raw_amount().to_string(),
+        gas_fee_token: pending.gas_fee.token.to_string(),
+        gas_fee_payer: pending.gas_fee.payer.to_string(),
+        tx_hash: tx_hash.to_vec(),
+    }
+}
+
+/// Returns the status changes of the pending transfers found in the events
+/// of a block, events with an invalid hash are ignored.
+pub(crate) fn bridge_pool_statuses(
+    block_results: &block_results::Response,
+) -> Vec<BridgePoolStatus> {
+    let events = block_results
+        .begin_block_events
+        .iter()
+        .flatten()
+        .chain(block_results.end_block_events.iter().flatten())
+        .chain(block_results.finalize_block_events.iter());
+
+    let mut statuses = vec![];
+
+    for event in events {
+        if !event.kind.ends_with(ETHEREUM_BRIDGE_EVENT) {
+            continue;
+        }
+
+        let attribute = |key: &str| {
+            event
+                .attributes
+                .iter()
+                .find(|attr| attr.key == key)
+                .map(|attr| attr.value.as_str())
+        };
+
+        if attribute("kind") != Some(BRIDGE_POOL_KIND) {
+            continue;
+        }
+
+        let (Some(transfer_hash), Some(status)) = (attribute("tx_hash"), attribute("status"))
+        else {
+            continue;
+        };
+
+        let Ok(transfer_hash) = hex::decode(transfer_hash.trim_start_matches("0x")) else {
+            continue;
+        };
+
+        statuses.push(BridgePoolStatus {
+            transfer_hash,
+            status: status.to_ascii_lowercase(),
+        });
+    }
+
+    statuses
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_sdk::types::{
+        eth_bridge_pool::{GasFee, TransferToEthereum},
+        ethereum_events::EthAddress,
+        token,
+    };
+    use tendermint::abci::Event;
+
+    use super::*;
+    use crate::test_utils::{address, block_results_with_events, ADDRESS as SENDER, NATIVE};
+
+    fn pending(kind: TransferToEthereumKind) -> PendingTransfer {
+        PendingTransfer {
+            transfer: TransferToEthereum {
+                kind,
+                asset: EthAddress([1; 20]),
+                recipient: EthAddress([2; 20]),
+                sender: address(SENDER),
+                amount: token::Amount::from_u64(100),
+            },
+            gas_fee: GasFee {
+                amount: token::Amount::from_u64(5),
+                payer: address(SENDER),
+                token: address(NATIVE),
+            },
+        }
+    }
+
+    #[test]
+    fn test_bridge_pool_transfers() {
+        let erc20 = pending(TransferToEthereumKind::Erc20);
+        let nut = pending(TransferToEthereumKind::Nut);
+        let txs = vec![
+            IndexedTx::applied("tx_bridge_pool", &[1], erc20.clone()),
+            IndexedTx::Decrypted {
+                fee_token: None,
+                gas_used: None,
+            },
+            IndexedTx::applied("tx_bridge_pool", &[2], nut.clone()),
+        ];
+
+        let transfers = bridge_pool_transfers(&txs);
+
+        assert_eq!(
+            transfers[0],
+            BridgePoolTransfer {
+                transfer_hash: erc20.keccak256().0.to_vec(),
+                kind: "erc20".to_string(),
+                asset: EthAddress([1; 20]).to_string(),
+                token: Address::Internal(InternalAddress::Erc20(EthAddress([1; 20]))).to_string(),
+                recipient: EthAddress([2; 20]).to_string(),
+                sender: SENDER.to_string(),
+                amount: "100".to_string(),
+                gas_fee: "5".to_string(),
+                gas_fee_token: NATIVE.to_string(),
+                gas_fee_payer: SENDER.to_string(),
+                tx_hash: vec![1],
+            }
+        );
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[1].kind, "nut");
+        assert_eq!(
+            transfers[1].token,
+            Address::Internal(InternalAddress::Nut(EthAddress([1; 20]))).to_string()
+        );
+        assert_eq!(transfers[1].transfer_hash, nut.keccak256().0.to_vec());
+    }
+
+    #[test]
+    fn test_bridge_pool_statuses() {
+        let block_results = block_results_with_events(vec![
+            Event::new(
+                "ethereum_bridge",
+                [
+                    ("kind", "bridge_pool"),
+                    ("tx_hash", "0x0a0b"),
+                    ("status", "Relayed"),
+                ],
+            ),
+            Event::new(
+                "ethereum_bridge",
+                [
+                    ("kind", "bridge_pool"),
+                    ("tx_hash", "0c"),
+                    ("status", "expired"),
+                ],
+            ),
+            // invalid hash
+            Event::new(
+                "ethereum_bridge",
+                [
+                    ("kind", "bridge_pool"),
+                    ("tx_hash", "0xzz"),
+                    ("status", "expired"),
+                ],
+            ),
+            Event::new(
+                "ethereum_bridge",
+                [
+                    ("kind", "other"),
+                    ("tx_hash", "0x0d"),
+                    ("status", "relayed"),
+                ],
+            ),
+            Event::new("applied", [("tx_hash", "0x0e"), ("status", "relayed")]),
+        ]);
+
+        assert_eq!(
+            bridge_pool_statuses(&block_results),
+            vec![
+                BridgePoolStatus {
+                    transfer_hash: vec![0x0a, 0x0b],
+                    status: "relayed".to_string(),
+                },
+                BridgePoolStatus {
+                    transfer_hash: vec![0x0c],
+                    status: "expired".to_string(),
+                },
+            ]
+        );
+    }
+}
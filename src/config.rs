@@ -25,6 +25,19 @@ pub const DEFAULT_LOG_FORMAT: &str = "pretty";
 #[derive(Debug, Deserialize)]
 pub struct IndexerConfig {
     pub tendermint_addr: String,
+    // Tokens to store in the tokens table along with the native token,
+    // so amounts can be shown with their alias and denomination.
+    #[serde(default)]
+    pub tokens: Vec<TokenConfig>,
+}
+
+/// A token known by the indexer.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TokenConfig {
+    pub address: String,
+    pub alias: String,
+    /// Number of decimal places, read from the node if not set.
+    pub denomination: Option<u8>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -46,6 +59,16 @@ pub struct DatabaseConfig {
     pub connection_timeout: Option<u64>,
     //  Give the option to skip the index creation
     pub create_index: bool,
+    // Keep the raw borsh bytes of every transaction in the
+    // raw_transactions table, so they can be decoded again later.
+    #[serde(default)]
+    pub store_raw_txs: bool,
+    // Compression applied to the raw transaction bytes before storing them.
+    #[serde(default)]
+    pub raw_txs_compression: RawTxCompression,
+    // What to do with transactions that can not be decoded.
+    #[serde(default)]
+    pub decode_policy: DecodePolicy,
 }
 
 const fn default_db_port() -> u16 {
@@ -94,6 +117,7 @@ impl Default for IndexerConfig {
     fn default() -> Self {
         Self {
             tendermint_addr: TENDERMINT_ADDR.to_owned(),
+            tokens: vec![],
         }
     }
 }
@@ -125,6 +149,9 @@ impl Default for DatabaseConfig {
             port: 5432,
             connection_timeout: None,
             create_index: true,
+            store_raw_txs: false,
+            raw_txs_compression: Default::default(),
+            decode_policy: Default::default(),
         }
     }
 }
@@ -137,6 +164,34 @@ pub enum LogFormat {
     Pretty,
 }
 
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum RawTxCompression {
+    #[default]
+    None,
+    Gzip,
+}
+
+impl RawTxCompression {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RawTxCompression::None => "none",
+            RawTxCompression::Gzip => "gzip",
+        }
+    }
+}
+
+/// How the indexer deals with transactions that can not be decoded.
+#[derive(Debug, Default, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum DecodePolicy {
+    /// Stop indexing with an error.
+    #[default]
+    Strict,
+    /// Save the block anyway, keeping track of the failure in `decode_failures`.
+    Lenient,
+}
+
 #[derive(Debug, Deserialize, clap::Parser)]
 pub struct CliSettings {
     #[clap(long, env, default_value = "")]
@@ -165,6 +220,12 @@ pub struct CliSettings {
     pub database_connection_timeout: Option<u64>,
     #[clap(long, env, default_value = "true")]
     pub database_create_index: bool,
+    #[clap(long, env, default_value = "false")]
+    pub database_store_raw_txs: bool,
+    #[clap(long, env, default_value = "none")]
+    pub database_raw_txs_compression: RawTxCompression,
+    #[clap(long, env, default_value = "strict")]
+    pub database_decode_policy: DecodePolicy,
     #[clap(long, env, default_value = TENDERMINT_ADDR)]
     pub indexer_tendermint_addr: String,
     #[clap(long, env, action=ArgAction::SetFalse)]
@@ -221,6 +282,9 @@ impl From<CliSettings> for Settings {
                 port: value.database_port,
                 connection_timeout: value.database_connection_timeout,
                 create_index: value.database_create_index,
+                store_raw_txs: value.database_store_raw_txs,
+                raw_txs_compression: value.database_raw_txs_compression,
+                decode_policy: value.database_decode_policy,
             },
             server: ServerConfig {
                 serve_at: value.server_serve_at,
@@ -229,6 +293,8 @@ impl From<CliSettings> for Settings {
             },
             indexer: IndexerConfig {
                 tendermint_addr: value.indexer_tendermint_addr,
+                // tokens can only be set in the configuration file
+                tokens: vec![],
             },
             jaeger: JaegerConfig {
                 enable: value.jaeger_enable,
@@ -248,20 +314,7 @@ impl Settings {
     pub fn new() -> Result<Self, Error> {
         // Try to read INDEXER_CONFIG_PATH env variable
         // otherwise use default settings.
-        if let Ok(path) = env::var(ENV_VAR_NAME) {
-            debug!("Reading configuration file from {}", path);
-
-            let config = Config::builder()
-                .add_source(File::with_name(&path))
-                .build()?;
-
-            let settings: Self = config.try_deserialize().map_err(Error::from)?;
-
-            // verify if chain_name is correct
-            if settings.chain_name.contains('.') {
-                panic!("chain_name cannot contains '.' (example of valid chain_name 'public-testnet-14')")
-            }
-
+        if let Some(settings) = Self::from_config_file()? {
             return Ok(settings);
         }
 
@@ -271,6 +324,32 @@ impl Settings {
         Ok(settings)
     }
 
+    /// Reads the settings from the configuration file pointed by the
+    /// INDEXER_CONFIG_PATH env variable, returns `None` if it is not set.
+    #[instrument(level = "debug")]
+    pub fn from_config_file() -> Result<Option<Self>, Error> {
+        let Ok(path) = env::var(ENV_VAR_NAME) else {
+            return Ok(None);
+        };
+
+        debug!("Reading configuration file from {}", path);
+
+        let config = Config::builder()
+            .add_source(File::with_name(&path))
+            .build()?;
+
+        let settings: Self = config.try_deserialize().map_err(Error::from)?;
+
+        // verify if chain_name is correct
+        if settings.chain_name.contains('.') {
+            panic!(
+                "chain_name cannot contains '.' (example of valid chain_name 'public-testnet-14')"
+            )
+        }
+
+        Ok(Some(settings))
+    }
+
     pub fn server_config(&self) -> &ServerConfig {
         &self.server
     }
@@ -0,0 +1,283 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::{query, Transaction};
+use tendermint_rpc::endpoint::block_results;
+use tracing::{debug, instrument};
+
+use crate::accounts;
+
+use super::{Database, IndexedTx};
+
+impl Database {
+    /// Update the accounts with the transactions of a block and record their
+    /// changes, it is up to the caller to call sqlx_tx.commit().await?; for
+    /// the changes to take place in database.
+    ///
+    /// Implicit accounts get a row when their public key is revealed, which
+    /// is also recorded in `revealed_public_keys`.
+    #[instrument(skip(txs, block_results, sqlx_tx, network))]
+    pub(super) async fn save_accounts<'a>(
+        txs: &[IndexedTx],
+        block_results: &block_results::Response,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let changes = accounts::account_changes(txs, block_results)?;
+
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving accounts");
+
+        let height = block_height as i32;
+
+        let account_query = account_update_query(network);
+
+        let history_query = format!(
+            "INSERT INTO {}.account_updates(address, kind, vp_code_hash, threshold, public_keys, height, tx_hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7);",
+            network
+        );
+
+        for change in changes {
+            query(&account_query)
+                .bind(&change.address)
+                .bind(&change.vp_code_hash)
+                .bind(change.threshold)
+                .bind(&change.public_keys)
+                .bind(height)
+                .execute(&mut *sqlx_tx)
+                .await?;
+
+            query(&history_query)
+                .bind(change.address)
+                .bind(change.kind)
+                .bind(change.vp_code_hash)
+                .bind(change.threshold)
+                .bind(change.public_keys)
+                .bind(height)
+                .bind(change.tx_hash)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        let revealed = accounts::revealed_public_keys(txs);
+
+        // a key can only be revealed once, the first reveal is kept
+        // if it is sent again anyway.
+        let reveal_query = format!(
+            "INSERT INTO {}.revealed_public_keys(public_key, address, height, tx_hash)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (public_key) DO NOTHING;",
+            network
+        );
+
+        for key in revealed {
+            query(&reveal_query)
+                .bind(key.public_key)
+                .bind(key.address)
+                .bind(height)
+                .bind(key.tx_hash)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Retrieves a historical list of thresholds associated with a given account.
+    ///
+    /// This function executes a SQL query to aggregate thresholds (`ARRAY_AGG`) for the specified
+    /// `account_id` from the `account_updates` table. The thresholds are ordered by height and
+    /// by the order the transactions were applied. The most recent threshold is at the end of the list.
+    ///
+    /// # Parameters
+    ///
+    /// - `account_id`: A string slice (`&str`) representing the unique identifier of the account.
+    ///
+    /// # Returns
+    ///
+    /// - On success, returns an `Option<Row>`. The `Row` contains an aggregated list
+    ///   of thresholds (aliased as `thresholds`) for the account. If `account_id` does not exists
+    ///   this will return Ok(None), otherwise Ok(Some(Row)) is returned, containing lists
+    ///   of all thresholds associated with that account, or an empty lists if no threshold updates
+    ///   have happend.
+    /// - On failure, returns an `Error`.
+    ///
+    /// # Usage
+    ///
+    /// This function is useful for tracking the evolution of thresholds associated with an account over time.
+    /// It provides a comprehensive history, allowing users or systems to understand how the thresholds
+    /// associated with the account have changed and to identify the current threshold in use.
+    pub async fn account_thresholds(&self, account_id: &str) -> Result<Option<Row>, Error> {
+        // NOTE: there are two scenarios:
+        // - account_id does not exists in such case this query will return Ok(None), because we
+        // use query.fetch_optional()
+        // - There are not updates including a threshold so far, in that case we use
+        // COALESCE which return a [] empty list instead of null.
+        let to_query = format!(
+            "
+            SELECT COALESCE(ARRAY_AGG(threshold ORDER BY height, id) FILTER (WHERE threshold IS NOT NULL), ARRAY[]::INTEGER[]) AS thresholds
+            FROM {}.account_updates
+            WHERE address = $1 GROUP BY address;
+            ",
+            self.network
+        );
+
+        query(&to_query)
+            .bind(account_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Retrieves a historical list of vp_code_hashes associated with a given account.
+    ///
+    /// This function executes a SQL query to aggregate vp_code_hashes (`ARRAY_AGG`) for the specified
+    /// `account_id` from the `account_updates` table. The hashes are ordered by height and by the
+    /// order the transactions were applied. The most recent hash is at the end of the list.
+    ///
+    /// # Parameters
+    ///
+    /// - `account_id`: A string slice (`&str`) representing the unique identifier of the account.
+    ///
+    /// # Returns
+    ///
+    /// - On success, returns an `Option<Row>`. The `Row` contains an aggregated list
+    ///   of vp_code_hashes (aliased as `code_hashes`) for the account. if `account_id` does not exists,
+    ///   it returns `Ok(None)`.
+    /// - On failure, returns an `Error`.
+    ///
+    /// # Usage
+    ///
+    /// This function is useful for tracking the evolution of vp_code_hashes associated with an account over time.
+    /// It provides a comprehensive history, allowing users or systems to understand how the vp_code_hashes
+    /// associated with the account have changed and to identify the current vp_code_hash in use.
+    pub async fn account_vp_codes(&self, account_id: &str) -> Result<Option<Row>, Error> {
+        // NOTE: there are two scenarios:
+        // - account_id does not exists in such case this query will return Ok(None), because we
+        // use query.fetch_optional()
+        // - There are not updates including vp_code_hashe so far, in that case we use
+        // COALESCE which return a [] empty list instead of null.
+        let to_query = format!(
+            "
+            SELECT COALESCE(ARRAY_AGG(vp_code_hash ORDER BY height, id) FILTER (WHERE vp_code_hash IS NOT NULL), ARRAY[]::BYTEA[]) AS code_hashes
+            FROM {}.account_updates
+            WHERE address = $1 GROUP BY address;
+            ",
+            self.network
+        );
+
+        query(&to_query)
+            .bind(account_id)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Retrieves a historical list of public key sets associated with a given account.
+    ///
+    /// This function returns a row for every update of the `account_updates` table that set the
+    /// public keys of the specified `account_id`, ordered by height and by the order the
+    /// transactions were applied. The most recent set is at the end of the list.
+    ///
+    /// # Parameters
+    ///
+    /// - `account_id`: A string slice (`&str`) representing the unique identifier of the account.
+    ///
+    /// # Returns
+    /// - On success, returns an empty list if there is no account_id or public_keys associated to it,
+    ///   otherwise the rows containing the public_keys_batches associated to this account.
+    /// - An `Error` on failure
+    ///
+    /// # Usage
+    ///
+    /// This function is useful for tracking the evolution of public keys associated with an account over time.
+    /// It provides a comprehensive history, allowing users or systems to understand how the account's
+    /// public keys have changed and to identify the current set of public keys.
+    pub async fn account_public_keys(&self, account_id: &str) -> Result<Vec<Row>, Error> {
+        let to_query = format!(
+            "
+            SELECT public_keys AS public_keys_batch
+            FROM {}.account_updates
+            WHERE address = $1 AND public_keys IS NOT NULL
+            ORDER BY height, id;
+        ",
+            self.network,
+        );
+
+        // Each returned row would contain a vector of public keys formatted as strings.
+        // The column's name is publick_key_batch.
+        query(&to_query)
+            .bind(account_id)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Returns the changes of the account `account_id`, oldest first.
+    ///
+    /// Values that were not changed by an update are null.
+    pub async fn account_history(&self, account_id: &str) -> Result<Vec<Row>, Error> {
+        let to_query = format!(
+            "SELECT kind, vp_code_hash, threshold, public_keys, height, tx_hash
+            FROM {}.account_updates
+            WHERE address = $1
+            ORDER BY height, id;",
+            self.network
+        );
+
+        query(&to_query)
+            .bind(account_id)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Returns the reveal of `public_key`, if it has been revealed.
+    pub async fn get_revealed_public_key(&self, public_key: &str) -> Result<Option<Row>, Error> {
+        let to_query = format!(
+            "SELECT public_key, address, height, tx_hash FROM {}.revealed_public_keys WHERE public_key = $1;",
+            self.network
+        );
+
+        query(&to_query)
+            .bind(public_key)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    /// Returns the public key revealed for the implicit `address`, if any.
+    pub async fn get_address_public_key(&self, address: &str) -> Result<Option<Row>, Error> {
+        let to_query = format!(
+            "SELECT public_key, address, height, tx_hash FROM {}.revealed_public_keys WHERE address = $1
+            ORDER BY height LIMIT 1;",
+            self.network
+        );
+
+        query(&to_query)
+            .bind(address)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+// Update an account with its columns in $2, $3 and $4 at height $5, a NULL
+// parameter leaves the column unchanged.
+fn account_update_query(network: &str) -> String {
+    format!(
+        "INSERT INTO {0}.accounts AS a(address, vp_code_hash, threshold, public_keys, height, last_update_height)
+        VALUES ($1, $2, $3, COALESCE($4, ARRAY[]::TEXT[]), $5, $5)
+        ON CONFLICT (address) DO UPDATE
+        SET vp_code_hash = COALESCE($2, a.vp_code_hash),
+            threshold = COALESCE($3, a.threshold),
+            public_keys = COALESCE($4, a.public_keys),
+            last_update_height = EXCLUDED.last_update_height;",
+        network
+    )
+}
@@ -0,0 +1,309 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+use sqlx::{query, QueryBuilder, Transaction};
+use std::collections::HashMap;
+use tendermint_rpc::endpoint::block_results;
+use tracing::{debug, instrument};
+
+use crate::balances::{self, BalanceChange, BlockBalanceChanges};
+use crate::bridge_pool::{self, BridgePoolTransfer};
+use crate::stewards;
+
+use super::{BlockState, Database, IndexedTx};
+
+impl Database {
+    /// Save the balance changes caused by the transactions and the events of a
+    /// block, it is up to the caller to call sqlx_tx.commit().await?; for the
+    /// changes to take place in database.
+    ///
+    /// The amount of reward claims is read from their event and the amount of
+    /// withdraws is the one of the unbonds they withdrew, so the delegations of
+    /// the block must have been saved before. The deposits of the proposals
+    /// ending in the block and the pending transfers of the bridge pool are
+    /// read from the tables saved with the previous blocks.
+    ///
+    /// The genesis balances are saved at height 0 along with the first block,
+    /// from the initial balances of its `state`. It fails if the native token,
+    /// the deposit of the proposals or the epoch of the withdraws are not part
+    /// of `state`.
+    #[instrument(skip(txs, block_results, state, sqlx_tx, network))]
+    pub(super) async fn save_balance_changes<'a>(
+        txs: &[IndexedTx],
+        block_results: &block_results::Response,
+        state: &BlockState,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let native_token = state.native_token.as_deref();
+        let balance_changes =
+            BlockBalanceChanges::from_txs(txs, block_results, state.min_proposal_fund.as_deref())?;
+
+        // proposals are executed at the beginning of the epoch, before the
+        // transactions of the block.
+        let mut changes = Self::proposal_balance_changes(block_results, sqlx_tx, network).await?;
+        let withdrawals =
+            Self::withdrawal_balance_changes(&balance_changes, state, sqlx_tx, network).await?;
+
+        changes.extend(balance_changes.changes);
+        changes.extend(withdrawals);
+        changes.extend(Self::bridge_pool_balance_changes(block_results, sqlx_tx, network).await?);
+
+        if let Some(initial_balances) = &state.initial_balances {
+            let native_token =
+                native_token.ok_or_else(|| Error::MissingBlockState("native token".into()))?;
+            let genesis = balances::genesis_changes(initial_balances, &changes, native_token)?;
+
+            Self::insert_balance_changes(genesis, Some(native_token), 0, sqlx_tx, network).await?;
+        }
+
+        Self::insert_balance_changes(changes, native_token, block_height, sqlx_tx, network).await
+    }
+
+    // The deposits of the proposals ending in the block, along with the retro
+    // payments of the passed ones. The deposit is the one recorded when the
+    // proposal has been submitted, nothing is returned for the proposals
+    // submitted before the first indexed block.
+    async fn proposal_balance_changes<'a>(
+        block_results: &block_results::Response,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<Vec<BalanceChange>, Error> {
+        let proposal_query = format!(
+            "SELECT p.author, p.kind_data, (-b.delta)::TEXT AS deposit FROM {0}.proposals p
+            LEFT JOIN {0}.balance_changes b
+                ON b.tx_hash = p.tx_hash AND b.address = p.author AND b.kind = 'proposal_deposit'
+            WHERE p.id = $1;",
+            network
+        );
+
+        let mut changes = vec![];
+
+        for (id, passed) in stewards::proposal_results(block_results) {
+            let Some(row) = query(&proposal_query)
+                .bind(id)
+                .fetch_optional(&mut *sqlx_tx)
+                .await?
+            else {
+                continue;
+            };
+
+            let author: String = row.try_get("author")?;
+            let deposit: Option<String> = row.try_get("deposit")?;
+            let kind_data: Option<serde_json::Value> = row.try_get("kind_data")?;
+
+            if let Some(deposit) = deposit {
+                changes.extend(balances::proposal_result_changes(&author, &deposit, passed));
+            }
+
+            if let Some(kind_data) = kind_data.filter(|_| passed) {
+                changes.extend(balances::pgf_payment_changes(&kind_data));
+            }
+        }
+
+        Ok(changes)
+    }
+
+    // The withdraws receive the unbonds marked as withdrawn by their
+    // transaction, which requires the epoch of the block.
+    async fn withdrawal_balance_changes<'a>(
+        balance_changes: &BlockBalanceChanges,
+        state: &BlockState,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<Vec<BalanceChange>, Error> {
+        if balance_changes.withdrawals.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let hashes: Vec<&[u8]> = balance_changes
+            .withdrawals
+            .iter()
+            .map(|w| w.tx_hash.as_slice())
+            .collect();
+
+        let rows = query(&format!(
+            "SELECT withdraw_tx_hash, SUM(amount)::TEXT AS amount FROM {}.unbonds
+            WHERE withdraw_tx_hash = ANY($1) GROUP BY withdraw_tx_hash;",
+            network
+        ))
+        .bind(hashes)
+        .fetch_all(&mut *sqlx_tx)
+        .await?;
+
+        let mut unbonded: HashMap<Vec<u8>, String> = HashMap::new();
+
+        for row in rows {
+            unbonded.insert(row.try_get("withdraw_tx_hash")?, row.try_get("amount")?);
+        }
+
+        balance_changes.withdrawal_changes(state.epoch, &unbonded)
+    }
+
+    // The refunds of the expired transfers of the bridge pool and the amounts
+    // of the relayed ones, the transfers are still pending until the bridge
+    // pool of the block is saved.
+    async fn bridge_pool_balance_changes<'a>(
+        block_results: &block_results::Response,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<Vec<BalanceChange>, Error> {
+        let transfer_query = format!(
+            "SELECT transfer_hash, kind, asset, token, recipient, sender, amount::TEXT AS amount,
+                gas_fee::TEXT AS gas_fee, gas_fee_token, gas_fee_payer, tx_hash
+            FROM {}.bridge_pool_transfers WHERE transfer_hash = $1 AND status = 'pending';",
+            network
+        );
+
+        let mut changes = vec![];
+
+        for status in bridge_pool::bridge_pool_statuses(block_results) {
+            let rows = query(&transfer_query)
+                .bind(&status.transfer_hash)
+                .fetch_all(&mut *sqlx_tx)
+                .await?;
+
+            for row in rows {
+                // the token of the transfers saved before it was recorded is unknown
+                let token: Option<String> = row.try_get("token")?;
+                let Some(token) = token else {
+                    continue;
+                };
+
+                let transfer = BridgePoolTransfer {
+                    transfer_hash: row.try_get("transfer_hash")?,
+                    kind: row.try_get("kind")?,
+                    asset: row.try_get("asset")?,
+                    token,
+                    recipient: row.try_get("recipient")?,
+                    sender: row.try_get("sender")?,
+                    amount: row.try_get("amount")?,
+                    gas_fee: row.try_get("gas_fee")?,
+                    gas_fee_token: row.try_get("gas_fee_token")?,
+                    gas_fee_payer: row.try_get("gas_fee_payer")?,
+                    tx_hash: row.try_get("tx_hash")?,
+                };
+
+                changes.extend(balances::bridge_pool_status_changes(
+                    &transfer,
+                    &status.status,
+                ));
+            }
+        }
+
+        Ok(changes)
+    }
+
+    /// Insert balance changes, a change without token being in `native_token`
+    /// which is then required.
+    async fn insert_balance_changes<'a>(
+        changes: Vec<BalanceChange>,
+        native_token: Option<&str>,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving balance changes");
+
+        let changes = changes
+            .into_iter()
+            .map(|change| {
+                let token = match change.token {
+                    Some(ref token) => token.clone(),
+                    None => native_token
+                        .ok_or_else(|| Error::MissingBlockState("native token".into()))?
+                        .to_string(),
+                };
+
+                Ok((change, token))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.balance_changes(
+                address,
+                token,
+                delta,
+                height,
+                tx_hash,
+                kind
+            )",
+            network
+        ));
+
+        query_builder
+            .push_values(changes, |mut b, (change, token)| {
+                b.push_bind(change.address)
+                    .push_bind(token)
+                    .push_bind(change.delta)
+                    .push_unseparated("::NUMERIC")
+                    .push_bind(block_height as i32)
+                    .push_bind(change.tx_hash)
+                    .push_bind(change.kind);
+            })
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the balance of `address` for every token it holds, as of
+    /// `height` if set.
+    pub async fn get_balances(
+        &self,
+        address: &str,
+        height: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT b.token, t.alias AS token_alias, t.denomination, SUM(b.delta)::TEXT AS balance
+            FROM {0}.balance_changes b LEFT JOIN {0}.tokens t ON t.address = b.token
+            WHERE b.address = $1 AND ($2::INTEGER IS NULL OR b.height <= $2)
+            GROUP BY b.token, t.alias, t.denomination ORDER BY b.token;",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .bind(height)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the balance changes of `address`, most recent first, along with
+    /// the balance after each change. By default the last 10 are returned.
+    pub async fn get_balance_changes(
+        &self,
+        address: &str,
+        token: Option<&str>,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT c.*, t.alias AS token_alias, t.denomination FROM (
+                SELECT id, token, delta::TEXT AS delta, (SUM(delta) OVER (PARTITION BY token ORDER BY id))::TEXT AS balance, height, tx_hash, kind
+                FROM {0}.balance_changes
+                WHERE address = $1 AND ($2::TEXT IS NULL OR token = $2)
+            ) c LEFT JOIN {0}.tokens t ON t.address = c.token ORDER BY c.id DESC LIMIT {1} OFFSET {2};",
+            self.network,
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .bind(address)
+            .bind(token)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
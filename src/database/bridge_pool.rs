@@ -0,0 +1,113 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::{query, Transaction};
+use tendermint_rpc::endpoint::block_results;
+use tracing::{debug, instrument};
+
+use crate::bridge_pool;
+
+use super::{Database, IndexedTx};
+
+impl Database {
+    /// Save the transfers added to the bridge pool by the transactions of a
+    /// block and update the status of the pending transfers that have been
+    /// relayed or have expired. It is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    #[instrument(skip(txs, block_results, sqlx_tx, network))]
+    pub(super) async fn save_bridge_pool<'a>(
+        txs: &[IndexedTx],
+        block_results: &block_results::Response,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let transfers = bridge_pool::bridge_pool_transfers(txs);
+        let statuses = bridge_pool::bridge_pool_statuses(block_results);
+
+        if transfers.is_empty() && statuses.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving bridge pool transfers");
+
+        let height = block_height as i32;
+
+        let transfer_query = format!(
+            "INSERT INTO {}.bridge_pool_transfers(transfer_hash, kind, asset, token, recipient,
+                sender, amount, gas_fee, gas_fee_token, gas_fee_payer, height, tx_hash)
+            VALUES ($1, $2, $3, $4, $5, $6, $7::NUMERIC, $8::NUMERIC, $9, $10, $11, $12);",
+            network
+        );
+
+        for transfer in transfers {
+            query(&transfer_query)
+                .bind(transfer.transfer_hash)
+                .bind(transfer.kind)
+                .bind(transfer.asset)
+                .bind(transfer.token)
+                .bind(transfer.recipient)
+                .bind(transfer.sender)
+                .bind(transfer.amount)
+                .bind(transfer.gas_fee)
+                .bind(transfer.gas_fee_token)
+                .bind(transfer.gas_fee_payer)
+                .bind(height)
+                .bind(transfer.tx_hash)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        // the same transfer can be added again once it expired
+        let status_query = format!(
+            "UPDATE {}.bridge_pool_transfers SET status = $2, status_height = $3
+            WHERE transfer_hash = $1 AND status = 'pending';",
+            network
+        );
+
+        for status in statuses {
+            query(&status_query)
+                .bind(status.transfer_hash)
+                .bind(status.status)
+                .bind(height)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the transfers to Ethereum sent or received by `address`, the
+    /// most recent first, optionally only the `pending` or the completed
+    /// (`relayed` or `expired`) ones.
+    pub async fn get_bridge_pool_transfers(
+        &self,
+        address: &str,
+        completed: Option<bool>,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT p.transfer_hash, p.kind, p.asset, p.token, p.recipient, p.sender,
+                p.amount::TEXT AS amount, a.denomination AS denomination, p.gas_fee::TEXT AS gas_fee,
+                p.gas_fee_token, t.alias AS gas_fee_token_alias, t.denomination AS gas_fee_denomination,
+                p.gas_fee_payer, p.status, p.height, p.status_height, p.tx_hash
+            FROM {0}.bridge_pool_transfers p LEFT JOIN {0}.tokens t ON t.address = p.gas_fee_token
+                LEFT JOIN {0}.tokens a ON a.address = p.token
+            WHERE (p.sender = $1 OR LOWER(p.recipient) = LOWER($1))
+                AND ($2::BOOLEAN IS NULL OR (p.status <> 'pending') = $2)
+            ORDER BY p.height DESC, p.id DESC LIMIT {1} OFFSET {2};",
+            self.network,
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .bind(address)
+            .bind(completed)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
@@ -0,0 +1,162 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::{query, Transaction};
+use tracing::{debug, instrument};
+
+use crate::fees;
+
+use super::{BlockState, Database, IndexedTx};
+
+impl Database {
+    /// Save the fees paid by the wrapper transactions of a block and the gas
+    /// used by its decrypted transactions, then update the rollups of the
+    /// affected epochs. It is up to the caller to call sqlx_tx.commit().await?;
+    /// for the changes to take place in database.
+    ///
+    /// The gas used is only known once the wrappers are decrypted, so it is
+    /// added to the fees of the previous block.
+    #[instrument(skip(txs, state, sqlx_tx, network))]
+    pub(super) async fn save_fees<'a>(
+        txs: &[IndexedTx],
+        state: &BlockState,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let block_fees = fees::block_fees(txs);
+        let gas_used = fees::gas_used(txs, block_height);
+
+        if block_fees.is_empty() && gas_used.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving fees");
+
+        let height = block_height as i32;
+
+        let fees_query = format!(
+            "INSERT INTO {0}.block_fees(height, epoch, token, tx_count, total_fee, gas_limit,
+                fees_per_gas, median_fee_per_gas)
+            SELECT $1, $2, $3, COUNT(*), SUM(f * g), SUM(g), $4::TEXT[]::NUMERIC[],
+                percentile_disc(0.5) WITHIN GROUP (ORDER BY f)
+            FROM UNNEST($4::TEXT[]::NUMERIC[], $5::BIGINT[]) AS u(f, g);",
+            network
+        );
+
+        for (token, token_fees) in block_fees {
+            query(&fees_query)
+                .bind(height)
+                .bind(state.epoch.map(|e| e as i32))
+                .bind(token)
+                .bind(token_fees.fees_per_gas)
+                .bind(token_fees.gas_limits)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        let gas_query = format!(
+            "UPDATE {0}.block_fees SET gas_used = gas_used + $3 WHERE height = $1 AND token = $2;",
+            network
+        );
+
+        let heights = fees::changed_heights(block_height, &gas_used);
+
+        for used in gas_used {
+            query(&gas_query)
+                .bind(used.height)
+                .bind(used.token)
+                .bind(used.gas)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        // recompute the rollups of the epochs of the changed blocks, the
+        // median can not be updated incrementally.
+        let epoch_query = format!(
+            "INSERT INTO {0}.epoch_fees(epoch, token, tx_count, total_fee, gas_limit, gas_used,
+                median_fee_per_gas, start_height, end_height)
+            SELECT b.epoch, b.token, SUM(b.tx_count), SUM(b.total_fee), SUM(b.gas_limit),
+                SUM(b.gas_used), (
+                    SELECT percentile_disc(0.5) WITHIN GROUP (ORDER BY f)
+                    FROM {0}.block_fees e, UNNEST(e.fees_per_gas) AS f
+                    WHERE e.epoch = b.epoch AND e.token = b.token
+                ), MIN(b.height), MAX(b.height)
+            FROM {0}.block_fees b
+            WHERE (b.epoch, b.token) IN (
+                SELECT epoch, token FROM {0}.block_fees WHERE height = ANY($1)
+            )
+            GROUP BY b.epoch, b.token
+            ON CONFLICT (epoch, token) DO UPDATE SET
+                tx_count = EXCLUDED.tx_count,
+                total_fee = EXCLUDED.total_fee,
+                gas_limit = EXCLUDED.gas_limit,
+                gas_used = EXCLUDED.gas_used,
+                median_fee_per_gas = EXCLUDED.median_fee_per_gas,
+                start_height = EXCLUDED.start_height,
+                end_height = EXCLUDED.end_height;",
+            network
+        );
+
+        query(&epoch_query)
+            .bind(heights)
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the fees paid per block and fee token, the most recent first.
+    pub async fn get_block_fees(
+        &self,
+        token: Option<&str>,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT f.height, f.epoch, f.token, t.alias AS token_alias, t.denomination, f.tx_count,
+                f.total_fee::TEXT AS total_fee, f.gas_limit, f.gas_used,
+                f.median_fee_per_gas::TEXT AS median_fee_per_gas
+            FROM {0}.block_fees f LEFT JOIN {0}.tokens t ON t.address = f.token
+            WHERE $1::TEXT IS NULL OR f.token = $1
+            ORDER BY f.height DESC, f.token LIMIT {1} OFFSET {2};",
+            self.network,
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .bind(token)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the fees paid per epoch and fee token, the most recent first.
+    pub async fn get_epoch_fees(
+        &self,
+        token: Option<&str>,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT f.epoch, f.token, t.alias AS token_alias, t.denomination, f.tx_count,
+                f.total_fee::TEXT AS total_fee, f.gas_limit, f.gas_used,
+                f.median_fee_per_gas::TEXT AS median_fee_per_gas, f.start_height, f.end_height
+            FROM {0}.epoch_fees f LEFT JOIN {0}.tokens t ON t.address = f.token
+            WHERE $1::TEXT IS NULL OR f.token = $1
+            ORDER BY f.epoch DESC, f.token LIMIT {1} OFFSET {2};",
+            self.network,
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .bind(token)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
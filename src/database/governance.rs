@@ -0,0 +1,193 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::{query, QueryBuilder, Transaction};
+use tracing::{debug, instrument};
+
+use crate::governance::BlockGovernance;
+
+use super::{BlockState, Database, IndexedTx};
+
+impl Database {
+    /// Save the proposals and votes of a block, it is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    ///
+    /// Only the last vote of a voter is kept, as it replaces the previous ones.
+    /// Voters are validators if they are in the validator registry when they
+    /// vote. The voting power of the voters of the proposals ending in the
+    /// block is read from `state`.
+    #[instrument(skip(txs, state, sqlx_tx, network))]
+    pub(super) async fn save_governance<'a>(
+        txs: &[IndexedTx],
+        state: &BlockState,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let governance = BlockGovernance::from_txs(txs)?;
+        let height = block_height as i32;
+
+        if !governance.proposals.is_empty() {
+            debug!("saving proposals");
+
+            let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+                "INSERT INTO {}.proposals(
+                    id,
+                    author,
+                    content_hash,
+                    content,
+                    kind,
+                    kind_data,
+                    voting_start_epoch,
+                    voting_end_epoch,
+                    grace_epoch,
+                    height,
+                    tx_hash
+                )",
+                network
+            ));
+
+            query_builder
+                .push_values(governance.proposals, |mut b, proposal| {
+                    b.push_bind(proposal.id)
+                        .push_bind(proposal.author)
+                        .push_bind(proposal.content_hash)
+                        .push_bind(proposal.content)
+                        .push_bind(proposal.kind)
+                        .push_bind(proposal.kind_data)
+                        .push_bind(proposal.voting_start_epoch)
+                        .push_bind(proposal.voting_end_epoch)
+                        .push_bind(proposal.grace_epoch)
+                        .push_bind(height)
+                        .push_bind(proposal.tx_hash);
+                })
+                .push(" ON CONFLICT (id) DO NOTHING")
+                .build()
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        if !governance.votes.is_empty() {
+            debug!("saving proposal votes");
+        }
+
+        let vote_query = format!(
+            "INSERT INTO {0}.proposal_votes(proposal_id, voter, vote, is_validator, height, tx_hash)
+            VALUES ($1, $2, $3, EXISTS (SELECT 1 FROM {0}.validators WHERE address = $2), $4, $5)
+            ON CONFLICT (proposal_id, voter) DO UPDATE
+            SET vote = EXCLUDED.vote, is_validator = EXCLUDED.is_validator, height = EXCLUDED.height, tx_hash = EXCLUDED.tx_hash
+            WHERE {0}.proposal_votes.height <= EXCLUDED.height;",
+            network
+        );
+
+        for vote in governance.votes {
+            query(&vote_query)
+                .bind(vote.proposal_id)
+                .bind(vote.voter)
+                .bind(vote.vote)
+                .bind(height)
+                .bind(vote.tx_hash)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        let Some(voting_powers) = &state.voting_powers else {
+            return Ok(());
+        };
+
+        debug!("saving voting powers");
+
+        let power_query = format!(
+            "UPDATE {}.proposal_votes SET voting_power = $3::NUMERIC
+            WHERE proposal_id = $1 AND voter = $2;",
+            network
+        );
+
+        for (proposal_id, voter, power) in voting_powers {
+            query(&power_query)
+                .bind(proposal_id)
+                .bind(voter)
+                .bind(power)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn vote_proposal_data(&self, proposal_id: i64) -> Result<Vec<Row>, Error> {
+        let query = format!(
+            "SELECT data FROM {}.transactions WHERE code = '\\xccdbe81f664ca6c2caa11426927093dc10ed95e75b3f2f45bffd8514fee47cd0' AND (data->>'id')::int = $1;",
+            self.network
+        );
+
+        // Execute the query and fetch the first row (if any)
+        sqlx::query(&query)
+            .bind(proposal_id)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the proposals and their vote tallies, the most recent first, by default the first 10.
+    pub async fn get_proposals(
+        &self,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "{} GROUP BY p.id ORDER BY p.id DESC LIMIT {} OFFSET {};",
+            proposals_select_query(&self.network),
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the proposal `id` and its vote tallies.
+    pub async fn get_proposal(&self, id: i64) -> Result<Option<Row>, Error> {
+        let str = format!(
+            "{} WHERE p.id = $1 GROUP BY p.id;",
+            proposals_select_query(&self.network)
+        );
+
+        query(&str)
+            .bind(id)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+// Selects the proposals along with the number of validators and delegators
+// that voted yay, nay and abstain and their voting power, to be completed with
+// a GROUP BY p.id clause. The voting power is NULL until the proposal ended.
+fn proposals_select_query(network: &str) -> String {
+    let mut tallies = vec![];
+
+    for (voter, is_validator) in [("validator", true), ("delegator", false)] {
+        for vote in ["yay", "nay", "abstain"] {
+            let filter = format!("v.is_validator = {is_validator} AND v.vote = '{vote}'");
+
+            tallies.push(format!(
+                "COUNT(v.voter) FILTER (WHERE {filter}) AS {voter}_{vote}_voters"
+            ));
+            tallies.push(format!(
+                "CASE WHEN COUNT(v.voting_power) > 0 THEN COALESCE(SUM(v.voting_power) FILTER (WHERE {filter}), 0)::TEXT END AS {voter}_{vote}_power"
+            ));
+        }
+    }
+
+    format!(
+        "SELECT p.id, p.author, p.content_hash, p.content, p.kind, p.kind_data, p.voting_start_epoch,
+        p.voting_end_epoch, p.grace_epoch, p.height, p.tx_hash, {}
+        FROM {network}.proposals p LEFT JOIN {network}.proposal_votes v ON v.proposal_id = p.id",
+        tallies.join(", ")
+    )
+}
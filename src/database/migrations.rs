@@ -0,0 +1,42 @@
+use crate::error::Error;
+
+use sqlx::query;
+use tracing::{info, instrument};
+
+use super::Database;
+
+impl Database {
+    /// Run the `statements` of the migration `name` within a postgres
+    /// transaction, unless it has already been applied.
+    ///
+    /// Migrations are meant for the changes of existing rows that can not
+    /// be expressed with `IF NOT EXISTS`, like filling a new table from the
+    /// indexed blocks, and are recorded in `schema_migrations` once applied.
+    #[instrument(skip(self, statements))]
+    pub(super) async fn migrate(&self, name: &str, statements: &[String]) -> Result<(), Error> {
+        let mut sqlx_tx = self.transaction().await?;
+
+        // a concurrent run waits for this transaction and then skips it
+        let applied = query(&format!(
+            "INSERT INTO {}.schema_migrations(name) VALUES ($1) ON CONFLICT (name) DO NOTHING;",
+            self.network
+        ))
+        .bind(name)
+        .execute(&mut *sqlx_tx)
+        .await?
+        .rows_affected()
+            == 0;
+
+        if applied {
+            return Ok(());
+        }
+
+        info!("Applying migration {}", name);
+
+        for statement in statements {
+            query(statement).execute(&mut *sqlx_tx).await?;
+        }
+
+        sqlx_tx.commit().await.map_err(Error::from)
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::query;
+use tracing::instrument;
+
+use super::Database;
+
+impl Database {
+    #[instrument(skip(self))]
+    /// Returns the number of blocks proposed by every validator between the heights `start`
+    /// and `end` (by default all the blocks), along with the number of blocks it was expected
+    /// to propose given its share of the voting power, the most blocks first.
+    pub async fn get_proposer_stats(
+        &self,
+        start: Option<i32>,
+        end: Option<i32>,
+    ) -> Result<Vec<Row>, Error> {
+        // the heights between two validator sets share the same expected proposers.
+        let str = format!(
+            "WITH bounds AS (
+                SELECT COALESCE($1, 0) AS start_height,
+                    COALESCE($2, (SELECT MAX(header_height) FROM {0}.blocks)) AS end_height
+            ),
+            sets AS (
+                SELECT height, LEAD(height) OVER (ORDER BY height) AS next_height,
+                    SUM(voting_power)::FLOAT AS total_power
+                FROM {0}.validator_sets GROUP BY height
+            ),
+            segments AS (
+                SELECT s.height, s.total_power,
+                    LEAST(b.end_height, COALESCE(s.next_height - 1, b.end_height)) - GREATEST(b.start_height, s.height) + 1 AS blocks
+                FROM sets s, bounds b
+            ),
+            expected AS (
+                SELECT v.consensus_address, SUM(s.blocks * v.voting_power::FLOAT / s.total_power) AS expected
+                FROM segments s JOIN {0}.validator_sets v ON v.height = s.height
+                WHERE s.blocks > 0 AND s.total_power > 0
+                GROUP BY v.consensus_address
+            ),
+            proposed AS (
+                SELECT DECODE(header_proposer_address, 'hex') AS consensus_address, COUNT(*) AS proposed,
+                    MAX(header_height) AS last_height
+                FROM {0}.blocks, bounds b
+                WHERE header_height BETWEEN b.start_height AND b.end_height
+                GROUP BY header_proposer_address
+            ),
+            counts AS (
+                SELECT COALESCE(p.consensus_address, e.consensus_address) AS consensus_address,
+                    COALESCE(p.proposed, 0) AS proposed, COALESCE(e.expected, 0) AS expected, p.last_height
+                FROM proposed p FULL OUTER JOIN expected e ON e.consensus_address = p.consensus_address
+            )
+            SELECT k.validator,
+                (ARRAY_AGG(c.consensus_address ORDER BY c.last_height DESC NULLS LAST))[1] AS consensus_address,
+                SUM(c.proposed)::BIGINT AS proposed, SUM(c.expected)::FLOAT AS expected,
+                MAX(c.last_height) AS last_height
+            FROM counts c
+            LEFT JOIN LATERAL (
+                SELECT validator FROM {0}.validator_consensus_keys
+                WHERE consensus_address = c.consensus_address ORDER BY height DESC LIMIT 1
+            ) k ON true
+            GROUP BY COALESCE(k.validator, ENCODE(c.consensus_address, 'hex')), k.validator
+            ORDER BY proposed DESC, expected DESC;",
+            self.network
+        );
+
+        query(&str)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the blocks proposed with any of the `validator_addresses`, the most recent
+    /// first, by default the first 10.
+    pub async fn get_proposed_blocks(
+        &self,
+        validator_addresses: &[Vec<u8>],
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        // proposer addresses are stored as upper case hex strings
+        let proposers: Vec<String> = validator_addresses.iter().map(hex::encode_upper).collect();
+
+        let str = format!(
+            "SELECT block_id, header_height, header_time FROM {}.blocks
+            WHERE header_proposer_address = ANY($1) ORDER BY header_height DESC LIMIT {} OFFSET {};",
+            self.network,
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .bind(proposers)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
@@ -0,0 +1,361 @@
+use crate::{
+    config::{DecodePolicy, RawTxCompression},
+    error::Error,
+    utils,
+};
+use clap::ValueEnum;
+
+use sqlx::Row as TRow;
+use sqlx::{query, Transaction};
+use std::collections::HashMap;
+use tendermint_rpc::endpoint::block_results;
+use tracing::{info, instrument, warn};
+
+use crate::decoder;
+
+use super::transactions::{decode_block_txs, is_sql_identifier};
+use super::{BlockState, Database, DERIVED_TABLES};
+
+impl Database {
+    /// Re-runs the transaction decoders over the raw transactions stored in
+    /// `raw_transactions`, updating `data` and `code_type` of the matching rows
+    /// in `transactions`.
+    ///
+    /// Transactions are selected by block height (both ends included) and,
+    /// optionally, by their type as resolved from the checksums. Only the
+    /// blocks that were saved with `store_raw_txs` enabled can be redecoded.
+    /// Previous decode failures of these transactions are replaced by the
+    /// failures of the new run, which follows the configured decode policy.
+    ///
+    /// If the data of any transaction changed, the derived tables are rebuilt
+    /// by replaying all the stored blocks, see
+    /// [`rebuild_derived_tables`](Self::rebuild_derived_tables).
+    ///
+    /// Returns the number of transactions that have been updated.
+    #[instrument(skip(self))]
+    pub async fn redecode(
+        &self,
+        from_height: Option<u32>,
+        to_height: Option<u32>,
+        code_type: Option<&str>,
+    ) -> Result<u64, Error> {
+        let last_height: Option<i32> = self.get_last_height().await?.try_get("header_height")?;
+        let Some(last_height) = last_height else {
+            // nothing has been indexed yet
+            return Ok(0);
+        };
+
+        let from_height = from_height.unwrap_or(1);
+        let to_height = to_height
+            .unwrap_or(last_height as u32)
+            .min(last_height as u32);
+
+        let policy = self.save_options.decode_policy;
+
+        let stored_query = format!(
+            "SELECT hash, code_type, data FROM {}.transactions WHERE block_id = $1;",
+            self.network
+        );
+
+        let update_query = format!(
+            "UPDATE {}.transactions SET data = $1, code_type = $2 WHERE hash = $3 AND block_id = $4;",
+            self.network
+        );
+
+        let delete_failures_query = format!(
+            "DELETE FROM {}.decode_failures WHERE tx_hash = $1 AND block_id = $2;",
+            self.network
+        );
+
+        // the transactions and the derived tables are updated at once, so
+        // they are never out of sync.
+        let mut sqlx_tx = self.transaction().await?;
+
+        let mut updated = 0;
+        let mut changed = false;
+        let mut failed_code_types = vec![];
+        let mut start = from_height;
+
+        while start <= to_height {
+            let end = start.saturating_add(REDECODE_BATCH_SIZE - 1).min(to_height);
+
+            info!("Redecoding transactions from height {} to {}", start, end);
+
+            for block in Self::stored_blocks(start, end, &mut sqlx_tx, &self.network).await? {
+                let wrappers =
+                    Self::previous_wrappers(block.height, &mut sqlx_tx, &self.network).await?;
+
+                let block_txs = decode_block_txs(
+                    &block.txs,
+                    &block.block_id,
+                    block.height,
+                    &block.results,
+                    &wrappers,
+                    policy,
+                )?;
+
+                let mut stored = HashMap::new();
+
+                for row in query(&stored_query)
+                    .bind(&block.block_id)
+                    .fetch_all(&mut *sqlx_tx)
+                    .await?
+                {
+                    let hash: Vec<u8> = row.try_get("hash")?;
+                    let code_type: Option<String> = row.try_get("code_type")?;
+                    let data: Option<serde_json::Value> = row.try_get("data")?;
+                    stored.insert(hash, (code_type, data));
+                }
+
+                for row in block_txs.rows {
+                    if row.tx_type != "Decrypted" {
+                        continue;
+                    }
+
+                    let type_tx = decoder::code_type(&row.code);
+
+                    if code_type.is_some_and(|c| c != type_tx.as_str()) {
+                        continue;
+                    }
+
+                    // the derived tables only need to be rebuilt if the data
+                    // they are built from changed.
+                    let (old_code_type, old_data) = stored.remove(&row.hash).unwrap_or_default();
+                    if old_code_type.as_deref() != Some(row.code_type.as_str())
+                        || old_data != row.data
+                    {
+                        changed = true;
+                    }
+
+                    query(&update_query)
+                        .bind(&row.data)
+                        .bind(&row.code_type)
+                        .bind(&row.hash)
+                        .bind(&block.block_id)
+                        .execute(&mut *sqlx_tx)
+                        .await?;
+
+                    // replace the rows the decoder stored for this transaction
+                    if let Some(decoder) = decoder::get_decoder(&type_tx) {
+                        for table in decoder.side_tables() {
+                            if !is_sql_identifier(&table) {
+                                return Err(Error::InvalidSideRow(table));
+                            }
+
+                            query(&format!(
+                                "DELETE FROM {}.{} WHERE tx_hash = $1;",
+                                self.network, table
+                            ))
+                            .bind(&row.hash)
+                            .execute(&mut *sqlx_tx)
+                            .await?;
+                        }
+                    }
+
+                    let tx_hash = decoder::bytea(&row.hash);
+                    let side_rows = block_txs
+                        .side_rows
+                        .iter()
+                        .filter(|side_row| side_row.values.get("tx_hash") == Some(&tx_hash))
+                        .cloned()
+                        .collect();
+
+                    Self::save_side_rows(side_rows, &mut sqlx_tx, &self.network).await?;
+
+                    query(&delete_failures_query)
+                        .bind(&row.hash)
+                        .bind(&block.block_id)
+                        .execute(&mut *sqlx_tx)
+                        .await?;
+
+                    let failures = block_txs
+                        .failures
+                        .iter()
+                        .filter(|failure| failure.0 == row.hash)
+                        .cloned()
+                        .collect();
+
+                    failed_code_types.extend(
+                        Self::save_decode_failures(failures, &mut sqlx_tx, &self.network).await?,
+                    );
+
+                    updated += 1;
+                }
+            }
+
+            start = end + 1;
+        }
+
+        if changed {
+            Self::rebuild_derived_tables(last_height as u64, policy, &mut sqlx_tx, &self.network)
+                .await?;
+        }
+
+        sqlx_tx.commit().await?;
+
+        Self::record_decode_failures(failed_code_types);
+
+        Ok(updated)
+    }
+
+    /// Rebuild the derived tables by replaying all the blocks up to
+    /// `last_height` from their raw transactions, results and state, the same
+    /// way they are saved when indexing. It is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    ///
+    /// Nothing is done if any block has been saved without its results, as
+    /// the tables can not be rebuilt from the blocks alone.
+    #[instrument(skip(sqlx_tx, network))]
+    async fn rebuild_derived_tables<'a>(
+        last_height: u64,
+        policy: DecodePolicy,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let missing: i64 = query(&format!(
+            "SELECT COUNT(*) AS missing FROM {0}.blocks b
+            WHERE NOT EXISTS (SELECT 1 FROM {0}.raw_block_results r WHERE r.block_id = b.block_id);",
+            network
+        ))
+        .fetch_one(&mut *sqlx_tx)
+        .await?
+        .try_get("missing")?;
+
+        if missing > 0 {
+            warn!(
+                "The derived tables have not been rebuilt, {} blocks have been saved without their results",
+                missing
+            );
+            return Ok(());
+        }
+
+        info!("Rebuilding the derived tables up to height {}", last_height);
+
+        let tables: Vec<String> = DERIVED_TABLES
+            .iter()
+            .map(|table| format!("{}.{}", network, table))
+            .collect();
+
+        query(&format!("TRUNCATE {};", tables.join(", ")))
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        // the decrypted transactions are linked to the wrappers of the
+        // previous block.
+        let mut wrappers = HashMap::new();
+        let mut start = 1;
+
+        while start <= last_height {
+            let end = start
+                .saturating_add(REDECODE_BATCH_SIZE as u64 - 1)
+                .min(last_height);
+
+            for block in Self::stored_blocks(start as u32, end as u32, sqlx_tx, network).await? {
+                let block_txs = decode_block_txs(
+                    &block.txs,
+                    &block.block_id,
+                    block.height,
+                    &block.results,
+                    &wrappers,
+                    policy,
+                )?;
+
+                Self::save_derived(
+                    &block_txs.indexed,
+                    &block.results,
+                    &block.state,
+                    block.height,
+                    sqlx_tx,
+                    network,
+                )
+                .await?;
+
+                wrappers = block_txs.wrappers();
+            }
+
+            start = end + 1;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the blocks from `from_height` to `to_height` (both included)
+    /// that have been saved with their raw transactions and results, in order.
+    async fn stored_blocks<'a>(
+        from_height: u32,
+        to_height: u32,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<Vec<StoredBlock>, Error> {
+        let rows = query(&format!(
+            "SELECT block_id, height, results, state FROM {}.raw_block_results
+            WHERE height BETWEEN $1 AND $2 ORDER BY height;",
+            network
+        ))
+        .bind(from_height as i32)
+        .bind(to_height as i32)
+        .fetch_all(&mut *sqlx_tx)
+        .await?;
+
+        let mut blocks = Vec::with_capacity(rows.len());
+
+        for row in rows {
+            let height: i32 = row.try_get("height")?;
+            let results: serde_json::Value = row.try_get("results")?;
+            let state: serde_json::Value = row.try_get("state")?;
+
+            blocks.push(StoredBlock {
+                block_id: row.try_get("block_id")?,
+                height: height as u64,
+                txs: vec![],
+                results: serde_json::from_value(results)?,
+                state: serde_json::from_value(state)?,
+            });
+        }
+
+        let rows = query(&format!(
+            "SELECT t.block_id, t.compression, t.raw FROM {0}.raw_transactions t
+            JOIN {0}.raw_block_results r ON r.block_id = t.block_id
+            WHERE r.height BETWEEN $1 AND $2 ORDER BY r.height, t.tx_index;",
+            network
+        ))
+        .bind(from_height as i32)
+        .bind(to_height as i32)
+        .fetch_all(&mut *sqlx_tx)
+        .await?;
+
+        let mut txs: HashMap<Vec<u8>, Vec<Vec<u8>>> = HashMap::new();
+
+        for row in rows {
+            let block_id: Vec<u8> = row.try_get("block_id")?;
+            let compression: String = row.try_get("compression")?;
+            let stored: Vec<u8> = row.try_get("raw")?;
+
+            let compression =
+                RawTxCompression::from_str(&compression, true).map_err(Error::InvalidTxData)?;
+
+            txs.entry(block_id)
+                .or_default()
+                .push(utils::decompress_raw_tx(compression, &stored)?);
+        }
+
+        for block in &mut blocks {
+            block.txs = txs.remove(&block.block_id).unwrap_or_default();
+        }
+
+        Ok(blocks)
+    }
+}
+
+// Number of blocks read at once when redecoding.
+const REDECODE_BATCH_SIZE: u32 = 100;
+
+/// A block saved with its raw transactions, results and state.
+struct StoredBlock {
+    block_id: Vec<u8>,
+    height: u64,
+    /// The raw transactions, in the order of the block.
+    txs: Vec<Vec<u8>>,
+    results: block_results::Response,
+    state: BlockState,
+}
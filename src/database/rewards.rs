@@ -0,0 +1,90 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::{query, QueryBuilder, Transaction};
+use tendermint_rpc::endpoint::block_results;
+use tracing::{debug, instrument};
+
+use crate::rewards;
+
+use super::{native_denomination_query, BlockState, Database, IndexedTx};
+
+impl Database {
+    /// Save the rewards claimed by the transactions of a block, it is up to the
+    /// caller to call sqlx_tx.commit().await?; for the changes to take place
+    /// in database.
+    ///
+    /// The amount of a claim is read from the event of its transaction, it is
+    /// NULL if the event does not tell it.
+    #[instrument(skip(txs, block_results, state, sqlx_tx, network))]
+    pub(super) async fn save_rewards<'a>(
+        txs: &[IndexedTx],
+        block_results: &block_results::Response,
+        state: &BlockState,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let claims = rewards::reward_claims(txs, block_results);
+
+        if claims.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving rewards");
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.rewards(delegator, validator, amount, epoch, height, tx_hash)",
+            network
+        ));
+
+        query_builder
+            .push_values(claims, |mut b, claim| {
+                b.push_bind(claim.delegator)
+                    .push_bind(claim.validator)
+                    .push_bind(claim.amount)
+                    .push_unseparated("::NUMERIC")
+                    .push_bind(state.epoch.map(|e| e as i32))
+                    .push_bind(block_height as i32)
+                    .push_bind(claim.tx_hash);
+            })
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the rewards claimed by `address` as a delegator, or from
+    /// `address` as a validator, per epoch, the most recent first.
+    ///
+    /// Claims whose amount is unknown are left out.
+    pub async fn get_claimed_rewards(
+        &self,
+        address: &str,
+        as_validator: bool,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT epoch, SUM(amount)::TEXT AS amount, COUNT(*) AS num_claims,
+                (SUM(SUM(amount)) OVER ())::TEXT AS total, ({0}) AS denomination
+            FROM {1}.rewards
+            WHERE {2} = $1 AND amount IS NOT NULL
+            GROUP BY epoch
+            ORDER BY epoch DESC NULLS LAST;",
+            native_denomination_query(&self.network),
+            self.network,
+            if as_validator {
+                "validator"
+            } else {
+                "delegator"
+            }
+        );
+
+        query(&str)
+            .bind(address)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
@@ -0,0 +1,98 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::{query, Transaction};
+use tracing::instrument;
+
+use crate::MASP_ADDR;
+
+use super::Database;
+
+impl Database {
+    /// Update the amount of every token held by the shielded pool with the
+    /// balance changes of the MASP address at `block_height`, it is up to the
+    /// caller to call sqlx_tx.commit().await?; for the changes to take place
+    /// in database.
+    #[instrument(skip(sqlx_tx, network))]
+    pub(super) async fn save_shielded_pool<'a>(
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let str = format!(
+            "INSERT INTO {0}.shielded_pool(token, height, delta, balance)
+            SELECT c.token, $2, c.delta, c.delta + COALESCE((
+                SELECT p.balance FROM {0}.shielded_pool p
+                WHERE p.token = c.token ORDER BY p.height DESC LIMIT 1
+            ), 0)
+            FROM (
+                SELECT token, SUM(delta) AS delta FROM {0}.balance_changes
+                WHERE address = $1 AND height = $2 GROUP BY token
+            ) c
+            WHERE c.delta <> 0;",
+            network
+        );
+
+        query(&str)
+            .bind(MASP_ADDR)
+            .bind(block_height as i32)
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the amount of every token held by the shielded pool, as of
+    /// `height` if set.
+    pub async fn get_shielded_pool(&self, height: Option<&i32>) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT DISTINCT ON (p.token) p.token, t.alias AS token_alias, t.denomination, p.balance::TEXT AS amount
+            FROM {0}.shielded_pool p LEFT JOIN {0}.tokens t ON t.address = p.token
+            WHERE $1::INTEGER IS NULL OR p.height <= $1
+            ORDER BY p.token, p.height DESC;",
+            self.network
+        );
+
+        query(&str)
+            .bind(height)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the amount of every token held by the shielded pool at the end
+    /// of every `interval` (e.g. `day`) it changed, along with the change
+    /// over the interval, oldest first.
+    pub async fn get_shielded_history(
+        &self,
+        interval: &str,
+        token: Option<&str>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT to_char(h.bucket, 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS time, h.token, t.alias AS token_alias,
+                t.denomination, h.balance::TEXT AS amount, h.change::TEXT AS change
+            FROM (
+                SELECT bucket, token, balance, SUM(delta) OVER w AS change,
+                    ROW_NUMBER() OVER (w ORDER BY height DESC) AS n
+                FROM (
+                    SELECT DATE_TRUNC('{1}', b.header_time::TIMESTAMPTZ AT TIME ZONE 'UTC') AS bucket,
+                        p.token, p.height, p.delta, p.balance
+                    FROM {0}.shielded_pool p JOIN {0}.blocks b ON b.header_height = p.height
+                    WHERE $1::TEXT IS NULL OR p.token = $1
+                ) p
+                WINDOW w AS (PARTITION BY token, bucket)
+            ) h LEFT JOIN {0}.tokens t ON t.address = h.token
+            WHERE h.n = 1
+            ORDER BY h.bucket, h.token;",
+            self.network, interval
+        );
+
+        query(&str)
+            .bind(token)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
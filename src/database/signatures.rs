@@ -0,0 +1,356 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+use sqlx::{query, QueryBuilder, Transaction};
+use std::collections::HashMap;
+use tendermint::block::Block;
+use tendermint_proto::types::{BlockIdFlag, CommitSig};
+use tracing::{debug, instrument};
+
+use super::Database;
+
+impl Database {
+    /// Save whether the validators signed the block committed by `block`, it is
+    /// up to the caller to call sqlx_tx.commit().await?; for the changes to take
+    /// place in database.
+    ///
+    /// Absent validators are not identified in the commit, the validator set of
+    /// the committed block is used to know who should have signed.
+    #[instrument(skip(block, sqlx_tx, network))]
+    pub(super) async fn save_validator_signatures<'a>(
+        block: &Block,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let Some(commit) = &block.last_commit else {
+            return Ok(());
+        };
+
+        let height = commit.height.value() as i32;
+
+        let rows = query(&format!(
+            "SELECT consensus_address FROM {0}.validator_sets
+            WHERE height = (SELECT MAX(height) FROM {0}.validator_sets WHERE height <= $1);",
+            network
+        ))
+        .bind(height)
+        .fetch_all(&mut *sqlx_tx)
+        .await?;
+
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving validator signatures");
+
+        let set = rows
+            .iter()
+            .map(|row| row.try_get("consensus_address"))
+            .collect::<Result<Vec<Vec<u8>>, _>>()?;
+        let commit_signatures: Vec<CommitSig> = commit
+            .signatures
+            .iter()
+            .map(|s| CommitSig::from(s.to_owned()))
+            .collect();
+
+        let signatures = set_signatures(set, &commit_signatures);
+
+        // the counters are only incremented once per height
+        let rows = {
+            let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+                "INSERT INTO {}.validator_signature_counters AS c(consensus_address, signed, missed, height)",
+                network
+            ));
+
+            let rows = query_builder
+                .push_values(&signatures, |mut b, (consensus_address, flag)| {
+                    let signed = (*flag == BlockIdFlag::Commit as i32) as i64;
+
+                    b.push_bind(consensus_address)
+                        .push_bind(signed)
+                        .push_bind(1 - signed)
+                        .push_bind(height);
+                })
+                .push(
+                    " ON CONFLICT (consensus_address) DO UPDATE
+                    SET signed = c.signed + EXCLUDED.signed, missed = c.missed + EXCLUDED.missed, height = EXCLUDED.height
+                    WHERE c.height < EXCLUDED.height
+                    RETURNING consensus_address, signed, missed",
+                )
+                .build()
+                .fetch_all(&mut *sqlx_tx)
+                .await?;
+
+            rows
+        };
+
+        let mut totals: HashMap<Vec<u8>, (i64, i64)> = HashMap::new();
+        for row in rows.iter() {
+            totals.insert(
+                row.try_get("consensus_address")?,
+                (row.try_get("signed")?, row.try_get("missed")?),
+            );
+        }
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.validator_signatures(consensus_address, height, block_id_flag, signed_total, missed_total)",
+            network
+        ));
+
+        let signatures: Vec<_> = signatures
+            .into_iter()
+            .filter_map(|(consensus_address, flag)| {
+                let (signed, missed) = totals.get(&consensus_address).copied()?;
+                Some((consensus_address, flag, signed, missed))
+            })
+            .collect();
+
+        if signatures.is_empty() {
+            return Ok(());
+        }
+
+        query_builder
+            .push_values(
+                signatures,
+                |mut b, (consensus_address, flag, signed, missed)| {
+                    b.push_bind(consensus_address)
+                        .push_bind(height)
+                        .push_bind(flag)
+                        .push_bind(signed)
+                        .push_bind(missed);
+                },
+            )
+            .push(" ON CONFLICT DO NOTHING")
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the number of heights in (`start`, `end`] at which the validator was part of the
+    /// validator set (`total`) and those it signed (`signed`), by default over the last
+    /// 500 heights. A validator has one address per consensus key. It is used to calculate the
+    /// validator uptime.
+    pub async fn validator_uptime(
+        &self,
+        validator_addresses: &[Vec<u8>],
+        start: Option<&i32>,
+        end: Option<&i32>,
+    ) -> Result<Row, Error> {
+        // the signatures of the last block are part of the next one
+        let q = format!(
+            "SELECT COUNT(*) FILTER (WHERE block_id_flag = {1}) AS signed, COUNT(*) AS total
+                FROM {0}.validator_signatures
+                WHERE consensus_address = ANY($1)
+                AND height > COALESCE($2, (SELECT MAX(header_height) FROM {0}.blocks) - 1 - {2})
+                AND height <= COALESCE($3, (SELECT MAX(header_height) FROM {0}.blocks) - 1);",
+            self.network,
+            BlockIdFlag::Commit as i32,
+            DEFAULT_UPTIME_WINDOW,
+        );
+
+        query(&q)
+            .bind(validator_addresses)
+            .bind(start)
+            .bind(end)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the number of heights signed and missed by every validator over the last
+    /// `window` heights, the highest uptime first.
+    ///
+    /// The running totals of the validators at the start of the window are subtracted
+    /// from their current counters, the counts of the consensus keys of a validator are
+    /// summed.
+    pub async fn get_validators_uptime(&self, window: i32) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "WITH counts AS (
+                SELECT c.consensus_address, c.height, c.signed - COALESCE(s.signed_total, 0) AS signed,
+                    c.missed - COALESCE(s.missed_total, 0) AS missed, k.validator
+                FROM {0}.validator_signature_counters c
+                LEFT JOIN LATERAL (
+                    SELECT signed_total, missed_total FROM {0}.validator_signatures
+                    WHERE consensus_address = c.consensus_address
+                    AND height <= (SELECT MAX(header_height) FROM {0}.blocks) - 1 - $1
+                    ORDER BY height DESC LIMIT 1
+                ) s ON true
+                LEFT JOIN LATERAL (
+                    SELECT validator FROM {0}.validator_consensus_keys
+                    WHERE consensus_address = c.consensus_address ORDER BY height DESC LIMIT 1
+                ) k ON true
+            )
+            SELECT validator, (ARRAY_AGG(consensus_address ORDER BY height DESC))[1] AS consensus_address,
+                SUM(signed)::BIGINT AS signed, SUM(missed)::BIGINT AS missed
+            FROM counts
+            GROUP BY COALESCE(validator, ENCODE(consensus_address, 'hex')), validator
+            HAVING SUM(signed) + SUM(missed) > 0
+            ORDER BY SUM(signed)::FLOAT / (SUM(signed) + SUM(missed)) DESC, validator;",
+            self.network
+        );
+
+        query(&str)
+            .bind(window)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns whether the validator signed, voted nil or was absent at every height in
+    /// (`start`, `end`] it was part of the validator set, the most recent first, by default the
+    /// first 10.
+    pub async fn get_validator_signatures(
+        &self,
+        validator_addresses: &[Vec<u8>],
+        start: Option<&i32>,
+        end: Option<&i32>,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT height, block_id_flag FROM {}.validator_signatures
+            WHERE consensus_address = ANY($1) AND {} ORDER BY height DESC LIMIT {} OFFSET {};",
+            self.network,
+            HEIGHT_WINDOW_FILTER,
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .bind(validator_addresses)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the last height signed by the validator.
+    pub async fn get_validator_last_signed_height(
+        &self,
+        validator_addresses: &[Vec<u8>],
+    ) -> Result<Row, Error> {
+        let str = format!(
+            "SELECT MAX(height) AS height FROM {}.validator_signatures
+            WHERE consensus_address = ANY($1) AND block_id_flag = {};",
+            self.network,
+            BlockIdFlag::Commit as i32,
+        );
+
+        query(&str)
+            .bind(validator_addresses)
+            .fetch_one(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the streaks of consecutive heights in (`start`, `end`] the validator did not
+    /// sign while being part of the validator set, the most recent first, by default the
+    /// first 10.
+    pub async fn get_validator_missed_streaks(
+        &self,
+        validator_addresses: &[Vec<u8>],
+        start: Option<&i32>,
+        end: Option<&i32>,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        // heights of a streak share the same difference between
+        // their rank among all heights and among missed heights.
+        let str = format!(
+            "SELECT MIN(height) AS start_height, MAX(height) AS end_height, COUNT(*) AS length
+            FROM (
+                SELECT height, block_id_flag, ROW_NUMBER() OVER (ORDER BY height)
+                    - ROW_NUMBER() OVER (PARTITION BY block_id_flag = {1} ORDER BY height) AS streak
+                FROM {0}.validator_signatures
+                WHERE consensus_address = ANY($1) AND {2}
+            ) s
+            WHERE block_id_flag <> {1}
+            GROUP BY streak ORDER BY start_height DESC LIMIT {3} OFFSET {4};",
+            self.network,
+            BlockIdFlag::Commit as i32,
+            HEIGHT_WINDOW_FILTER,
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .bind(validator_addresses)
+            .bind(start)
+            .bind(end)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+/// Number of heights over which the validator uptime is computed
+/// when no range is given.
+pub const DEFAULT_UPTIME_WINDOW: i32 = 500;
+
+// Keeps the heights in ($2, $3], either bound being optional.
+const HEIGHT_WINDOW_FILTER: &str =
+    "($2::INTEGER IS NULL OR height > $2) AND ($3::INTEGER IS NULL OR height <= $3)";
+
+/// Returns the block id flag of every validator of `set` in the signatures of
+/// a commit, absent for the ones missing from it.
+fn set_signatures(set: Vec<Vec<u8>>, signatures: &[CommitSig]) -> Vec<(Vec<u8>, i32)> {
+    let flags: HashMap<&[u8], i32> = signatures
+        .iter()
+        .filter(|s| !s.validator_address.is_empty())
+        .map(|s| (s.validator_address.as_slice(), s.block_id_flag))
+        .collect();
+
+    set.into_iter()
+        .map(|consensus_address| {
+            let flag = flags
+                .get(consensus_address.as_slice())
+                .copied()
+                .unwrap_or(BlockIdFlag::Absent as i32);
+
+            (consensus_address, flag)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_signatures() {
+        let signature = |address: u8, flag: BlockIdFlag| CommitSig {
+            block_id_flag: flag as i32,
+            validator_address: vec![address],
+            ..Default::default()
+        };
+        let signatures = [
+            signature(1, BlockIdFlag::Commit),
+            signature(2, BlockIdFlag::Nil),
+            // not part of the set
+            signature(4, BlockIdFlag::Commit),
+            // absent signatures have no address
+            CommitSig {
+                block_id_flag: BlockIdFlag::Absent as i32,
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            set_signatures(vec![vec![1], vec![2], vec![3]], &signatures),
+            vec![
+                (vec![1], BlockIdFlag::Commit as i32),
+                (vec![2], BlockIdFlag::Nil as i32),
+                (vec![3], BlockIdFlag::Absent as i32),
+            ]
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::{query, Transaction};
+use tendermint_rpc::endpoint::block_results;
+use tracing::{debug, instrument};
+
+use crate::slashes::BlockSlashes;
+
+use super::{native_denomination_query, Database, IndexedTx};
+
+impl Database {
+    /// Save the slashes and jailings of a block, it is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    ///
+    /// Slashes are linked to the evidence of the infraction when the consensus
+    /// keys of the validator are known.
+    #[instrument(skip(txs, block_results, sqlx_tx, network))]
+    pub(super) async fn save_slashes<'a>(
+        txs: &[IndexedTx],
+        block_results: &block_results::Response,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let BlockSlashes { slashes, jailings } = BlockSlashes::new(block_results, txs);
+        let height = block_height as i32;
+
+        if !slashes.is_empty() {
+            debug!("saving slashes");
+        }
+
+        let slash_query = format!(
+            "INSERT INTO {0}.slashes(validator, infraction, infraction_height, rate, amount, height, evidence_block_id)
+            VALUES ($1, $2, $3, $4::NUMERIC, $5::NUMERIC, $6, (
+                SELECT block_id FROM {0}.evidences
+                WHERE height = $3 AND address IN (
+                    SELECT consensus_address FROM {0}.validator_consensus_keys WHERE validator = $1
+                )
+                LIMIT 1
+            ));",
+            network
+        );
+
+        for slash in slashes {
+            query(&slash_query)
+                .bind(slash.validator)
+                .bind(slash.infraction)
+                .bind(slash.infraction_height)
+                .bind(slash.rate)
+                .bind(slash.amount)
+                .bind(height)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        let jailing_query = format!(
+            "INSERT INTO {}.jailings(validator, jailed, reason, height, tx_hash)
+            VALUES ($1, $2, $3, $4, $5);",
+            network
+        );
+
+        // unjailed validators are already set as active
+        let jailed_query = jailed_validator_query(network);
+
+        for jailing in jailings {
+            if jailing.jailed {
+                query(&jailed_query)
+                    .bind(&jailing.validator)
+                    .bind(height)
+                    .execute(&mut *sqlx_tx)
+                    .await?;
+            }
+
+            query(&jailing_query)
+                .bind(jailing.validator)
+                .bind(jailing.jailed)
+                .bind(jailing.reason)
+                .bind(height)
+                .bind(jailing.tx_hash)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the slashes of `validator`, or of all the validators if `None`, the most
+    /// recent first, by default the first 10.
+    pub async fn get_slashes(
+        &self,
+        validator: Option<&str>,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT validator, infraction, infraction_height, rate::TEXT AS rate, amount::TEXT AS amount,
+            ({}) AS denomination, height, evidence_block_id FROM {}.slashes
+            WHERE $1::TEXT IS NULL OR validator = $1 ORDER BY id DESC LIMIT {} OFFSET {};",
+            native_denomination_query(&self.network),
+            self.network,
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .bind(validator)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns when `validator` was jailed and unjailed, the most recent first.
+    pub async fn get_jailings(&self, validator: &str) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT jailed, reason, height, tx_hash FROM {}.jailings WHERE validator = $1 ORDER BY id DESC;",
+            self.network
+        );
+
+        query(&str)
+            .bind(validator)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+// Set the validator $1 as jailed at height $2.
+fn jailed_validator_query(network: &str) -> String {
+    format!(
+        "INSERT INTO {0}.validators(address, state, last_change_height)
+        VALUES ($1, 'jailed', $2)
+        ON CONFLICT (address) DO UPDATE
+        SET state = 'jailed', last_change_height = EXCLUDED.last_change_height;",
+        network
+    )
+}
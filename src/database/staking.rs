@@ -0,0 +1,222 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::{query, Transaction};
+use tracing::{debug, instrument, warn};
+
+use crate::staking::{self, StakingChange};
+
+use super::{native_denomination_query, BlockState, Database, IndexedTx};
+
+impl Database {
+    /// Update the delegations with the staking transactions of a block, it is
+    /// up to the caller to call sqlx_tx.commit().await?; for the changes to
+    /// take place in database.
+    ///
+    /// Epochs are only known when they are part of `state`, otherwise withdraws
+    /// can not tell which unbonds they withdraw and are skipped.
+    #[instrument(skip(txs, state, sqlx_tx, network))]
+    pub(super) async fn save_delegations<'a>(
+        txs: &[IndexedTx],
+        state: &BlockState,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let changes = staking::staking_changes(txs);
+
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving delegations");
+
+        let height = block_height as i32;
+        let epoch = state.epoch.map(|e| e as i32);
+        let withdrawable_epoch = state
+            .epoch
+            .zip(state.withdrawable_epoch_offset)
+            .map(|(e, offset)| (e + offset) as i32);
+
+        let delegation_query = delegation_update_query(network);
+
+        let unbond_query = format!(
+            "INSERT INTO {}.unbonds(delegator, validator, amount, epoch, withdrawable_epoch, height, tx_hash)
+            VALUES ($1, $2, $3::NUMERIC, $4, $5, $6, $7);",
+            network
+        );
+
+        let redelegation_query = format!(
+            "INSERT INTO {}.redelegations(delegator, src_validator, dest_validator, amount, epoch, height, tx_hash)
+            VALUES ($1, $2, $3, $4::NUMERIC, $5, $6, $7);",
+            network
+        );
+
+        let withdraw_query = format!(
+            "UPDATE {}.unbonds SET withdraw_height = $1, withdraw_tx_hash = $2
+            WHERE delegator = $3 AND validator = $4 AND withdraw_tx_hash IS NULL AND withdrawable_epoch <= $5;",
+            network
+        );
+
+        for change in changes {
+            for (delegator, validator, delta) in change.delegation_deltas() {
+                query(&delegation_query)
+                    .bind(delegator)
+                    .bind(validator)
+                    .bind(delta)
+                    .bind(height)
+                    .execute(&mut *sqlx_tx)
+                    .await?;
+            }
+
+            match change {
+                StakingChange::Bond { .. } => {}
+                StakingChange::Unbond {
+                    delegator,
+                    validator,
+                    amount,
+                    tx_hash,
+                } => {
+                    query(&unbond_query)
+                        .bind(delegator)
+                        .bind(validator)
+                        .bind(amount)
+                        .bind(epoch)
+                        .bind(withdrawable_epoch)
+                        .bind(height)
+                        .bind(tx_hash)
+                        .execute(&mut *sqlx_tx)
+                        .await?;
+                }
+                StakingChange::Redelegation {
+                    delegator,
+                    src_validator,
+                    dest_validator,
+                    amount,
+                    tx_hash,
+                } => {
+                    query(&redelegation_query)
+                        .bind(delegator)
+                        .bind(src_validator)
+                        .bind(dest_validator)
+                        .bind(amount)
+                        .bind(epoch)
+                        .bind(height)
+                        .bind(tx_hash)
+                        .execute(&mut *sqlx_tx)
+                        .await?;
+                }
+                StakingChange::Withdraw {
+                    delegator,
+                    validator,
+                    tx_hash,
+                } => {
+                    let Some(epoch) = epoch else {
+                        warn!(
+                            "Skipping withdraw of {} from {}, the epoch is unknown",
+                            delegator, validator
+                        );
+                        continue;
+                    };
+
+                    query(&withdraw_query)
+                        .bind(height)
+                        .bind(tx_hash)
+                        .bind(delegator)
+                        .bind(validator)
+                        .bind(epoch)
+                        .execute(&mut *sqlx_tx)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the validators `delegator` has tokens bonded to.
+    pub async fn get_delegations(&self, delegator: &str) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT validator, amount::TEXT AS amount, height, ({0}) AS denomination FROM {1}.delegations d
+            WHERE delegator = $1 AND amount > 0 ORDER BY d.amount DESC;",
+            native_denomination_query(&self.network),
+            self.network
+        );
+
+        query(&str)
+            .bind(delegator)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the unbonds of `delegator` that have not been withdrawn yet.
+    pub async fn get_pending_unbonds(&self, delegator: &str) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT validator, amount::TEXT AS amount, epoch, withdrawable_epoch, height, tx_hash, ({0}) AS denomination
+            FROM {1}.unbonds WHERE delegator = $1 AND withdraw_tx_hash IS NULL ORDER BY height;",
+            native_denomination_query(&self.network),
+            self.network
+        );
+
+        query(&str)
+            .bind(delegator)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the redelegations of `delegator`, most recent first.
+    pub async fn get_redelegations(&self, delegator: &str) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT src_validator, dest_validator, amount::TEXT AS amount, epoch, height, tx_hash, ({0}) AS denomination
+            FROM {1}.redelegations WHERE delegator = $1 ORDER BY height DESC;",
+            native_denomination_query(&self.network),
+            self.network
+        );
+
+        query(&str)
+            .bind(delegator)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the delegators of `validator` and their bonded amount, by default the first 10.
+    pub async fn get_delegators(
+        &self,
+        validator: &str,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT delegator, amount::TEXT AS amount, height, ({0}) AS denomination FROM {1}.delegations d
+            WHERE validator = $1 AND amount > 0 ORDER BY d.amount DESC LIMIT {2} OFFSET {3};",
+            native_denomination_query(&self.network),
+            self.network,
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .bind(validator)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+// Add $3 to the amount bonded by $1 to $2 at height $4, negative to remove it.
+fn delegation_update_query(network: &str) -> String {
+    format!(
+        "INSERT INTO {0}.delegations(delegator, validator, amount, height)
+        VALUES ($1, $2, $3::NUMERIC, $4)
+        ON CONFLICT (delegator, validator) DO UPDATE
+        SET amount = {0}.delegations.amount + EXCLUDED.amount, height = EXCLUDED.height;",
+        network
+    )
+}
@@ -0,0 +1,142 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::query;
+use sqlx::Row as TRow;
+use tracing::instrument;
+
+use super::Database;
+
+impl Database {
+    /// Roll the next blocks up into the statistics of every interval.
+    ///
+    /// Every call starts over from the first block of the last bucket, which
+    /// may have been incomplete, and goes through at most
+    /// `STATS_BATCH_SIZE` blocks. Returns whether blocks are left.
+    #[instrument(skip(self))]
+    pub async fn update_stats(&self) -> Result<bool, Error> {
+        let last_height: Option<i32> = self.get_last_height().await?.try_get("header_height")?;
+        let Some(last_height) = last_height else {
+            // nothing has been indexed yet
+            return Ok(false);
+        };
+
+        let mut pending = false;
+
+        for interval in STATS_INTERVALS {
+            let start_query = format!(
+                "SELECT COALESCE(
+                    (SELECT start_height FROM {0}.stats_{1} ORDER BY bucket DESC LIMIT 1),
+                    (SELECT MIN(header_height) FROM {0}.blocks)
+                ) AS start_height;",
+                self.network, interval
+            );
+
+            let start: i32 = query(&start_query)
+                .fetch_one(&*self.pool)
+                .await?
+                .try_get("start_height")?;
+            let end = start.saturating_add(STATS_BATCH_SIZE);
+
+            let stats_query = format!(
+                "WITH b AS (
+                    SELECT block_id, header_height AS height,
+                        header_time::TIMESTAMPTZ AT TIME ZONE 'UTC' AS time,
+                        DATE_TRUNC('{1}', header_time::TIMESTAMPTZ AT TIME ZONE 'UTC') AS bucket
+                    FROM {0}.blocks WHERE header_height >= $1 AND header_height < $2
+                ), blocks AS (
+                    SELECT bucket, MIN(height) AS start_height, MAX(height) AS end_height,
+                        COUNT(*) AS num_blocks,
+                        EXTRACT(EPOCH FROM MAX(time) - MIN(time)) / NULLIF(COUNT(*) - 1, 0) AS avg_block_interval
+                    FROM b GROUP BY bucket
+                ), code_types AS (
+                    SELECT b.bucket, COALESCE(t.code_type, 'unknown') AS code_type, COUNT(*) AS num_txs,
+                        COUNT(*) FILTER (WHERE t.return_code = 0) AS num_success
+                    FROM b JOIN {0}.transactions t ON t.block_id = b.block_id
+                    WHERE t.tx_type = 'Decrypted'
+                    GROUP BY b.bucket, COALESCE(t.code_type, 'unknown')
+                ), txs AS (
+                    SELECT bucket, SUM(num_txs) AS num_txs, SUM(num_success) AS num_success,
+                        JSONB_OBJECT_AGG(code_type, num_txs) AS txs_by_code_type
+                    FROM code_types GROUP BY bucket
+                ), signers AS (
+                    SELECT b.bucket, COUNT(DISTINCT c.address) AS unique_signers
+                    FROM b JOIN {0}.balance_changes c ON c.height = b.height
+                    WHERE c.kind = 'fee'
+                    GROUP BY b.bucket
+                )
+                INSERT INTO {0}.stats_{1}(bucket, start_height, end_height, num_blocks, num_txs,
+                    num_success, txs_by_code_type, unique_signers, avg_block_interval)
+                SELECT blocks.bucket, blocks.start_height, blocks.end_height, blocks.num_blocks,
+                    COALESCE(txs.num_txs, 0), COALESCE(txs.num_success, 0),
+                    COALESCE(txs.txs_by_code_type, '{{}}'::JSONB), COALESCE(signers.unique_signers, 0),
+                    blocks.avg_block_interval
+                FROM blocks
+                LEFT JOIN txs ON txs.bucket = blocks.bucket
+                LEFT JOIN signers ON signers.bucket = blocks.bucket
+                ON CONFLICT (bucket) DO UPDATE SET
+                    start_height = EXCLUDED.start_height,
+                    end_height = EXCLUDED.end_height,
+                    num_blocks = EXCLUDED.num_blocks,
+                    num_txs = EXCLUDED.num_txs,
+                    num_success = EXCLUDED.num_success,
+                    txs_by_code_type = EXCLUDED.txs_by_code_type,
+                    unique_signers = EXCLUDED.unique_signers,
+                    avg_block_interval = EXCLUDED.avg_block_interval;",
+                self.network, interval
+            );
+
+            query(&stats_query)
+                .bind(start)
+                .bind(end)
+                .execute(&*self.pool)
+                .await?;
+
+            pending |= end <= last_height;
+        }
+
+        Ok(pending)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the statistics of the last `num` buckets of `interval`, either
+    /// `hour` or `day`, starting between `from` and `to`, oldest first.
+    pub async fn get_stats(
+        &self,
+        interval: &str,
+        from: Option<&str>,
+        to: Option<&str>,
+        num: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT to_char(bucket, 'YYYY-MM-DD\"T\"HH24:MI:SS\"Z\"') AS time, start_height, end_height,
+                num_blocks, num_txs, txs_by_code_type,
+                num_success::FLOAT8 / NULLIF(num_txs, 0) AS success_rate,
+                unique_signers, avg_block_interval
+            FROM (
+                SELECT * FROM {0}.stats_{1}
+                WHERE ($1::TEXT IS NULL OR bucket >= $1::TEXT::TIMESTAMPTZ AT TIME ZONE 'UTC')
+                    AND ($2::TEXT IS NULL OR bucket <= $2::TEXT::TIMESTAMPTZ AT TIME ZONE 'UTC')
+                ORDER BY bucket DESC LIMIT {2}
+            ) s
+            ORDER BY bucket;",
+            self.network,
+            interval,
+            num.unwrap_or(&100)
+        );
+
+        query(&str)
+            .bind(from)
+            .bind(to)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+// Length of the buckets of the statistics tables, `stats_hour` and `stats_day`.
+pub(super) const STATS_INTERVALS: [&str; 2] = ["hour", "day"];
+
+// Number of blocks rolled up into the statistics at once, the blocks
+// of a day must fit within it.
+const STATS_BATCH_SIZE: i32 = 50_000;
@@ -0,0 +1,154 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+use sqlx::{query, Transaction};
+use tendermint_rpc::endpoint::block_results;
+use tracing::{debug, instrument};
+
+use crate::stewards;
+
+use super::{Database, IndexedTx};
+
+impl Database {
+    /// Update the PGF stewards with the `PGFSteward` proposals executed in a
+    /// block and the steward transactions, and record their changes. It is up
+    /// to the caller to call sqlx_tx.commit().await?; for the changes to take
+    /// place in database.
+    ///
+    /// Proposals are executed at the beginning of the epoch, before the
+    /// transactions of the block.
+    #[instrument(skip(txs, block_results, sqlx_tx, network))]
+    pub(super) async fn save_stewards<'a>(
+        txs: &[IndexedTx],
+        block_results: &block_results::Response,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let mut changes = vec![];
+
+        let proposal_query = format!(
+            "SELECT kind_data FROM {}.proposals WHERE id = $1 AND kind = 'PGFSteward';",
+            network
+        );
+
+        for id in stewards::passed_proposals(block_results) {
+            let row = query(&proposal_query)
+                .bind(id)
+                .fetch_optional(&mut *sqlx_tx)
+                .await?;
+
+            if let Some(row) = row {
+                let kind_data: Option<serde_json::Value> = row.try_get("kind_data")?;
+                if let Some(kind_data) = kind_data {
+                    changes.extend(stewards::proposal_steward_changes(id, &kind_data));
+                }
+            }
+        }
+
+        changes.extend(stewards::steward_changes(txs)?);
+
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving stewards");
+
+        let height = block_height as i32;
+
+        let steward_query = steward_update_query(network);
+
+        let change_query = format!(
+            "INSERT INTO {}.steward_changes(address, kind, commission, proposal_id, height, tx_hash)
+            VALUES ($1, $2, $3, $4, $5, $6);",
+            network
+        );
+
+        for change in changes {
+            query(&steward_query)
+                .bind(&change.address)
+                .bind(change.status())
+                .bind(&change.commission)
+                .bind(height)
+                .execute(&mut *sqlx_tx)
+                .await?;
+
+            query(&change_query)
+                .bind(change.address)
+                .bind(change.kind)
+                .bind(change.commission)
+                .bind(change.proposal_id)
+                .bind(height)
+                .bind(change.tx_hash)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the PGF stewards, only the active ones unless `all` is set.
+    pub async fn get_stewards(&self, all: bool) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT address, status, commission, height, last_change_height FROM {}.stewards
+            WHERE $1 OR status = 'active'
+            ORDER BY height, address;",
+            self.network
+        );
+
+        query(&str)
+            .bind(all)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the PGF steward `address`, whatever its status.
+    pub async fn get_steward(&self, address: &str) -> Result<Option<Row>, Error> {
+        let str = format!(
+            "SELECT address, status, commission, height, last_change_height FROM {}.stewards WHERE address = $1;",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the changes of the commission split of the steward `address`,
+    /// oldest first.
+    pub async fn get_steward_commissions(&self, address: &str) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT kind, commission, proposal_id, height, tx_hash FROM {}.steward_changes
+            WHERE address = $1 AND commission IS NOT NULL
+            ORDER BY height, id;",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+// Update a steward with its status in $2 and commission in $3 at height $4,
+// stewards only known from their transactions are active.
+fn steward_update_query(network: &str) -> String {
+    format!(
+        "INSERT INTO {}.stewards AS s(address, status, commission, height, last_change_height)
+        VALUES ($1, COALESCE($2, 'active'), $3, $4, $4)
+        ON CONFLICT (address) DO UPDATE
+        SET status = COALESCE($2, s.status),
+            commission = COALESCE($3, s.commission),
+            last_change_height = $4;",
+        network
+    )
+}
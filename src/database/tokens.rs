@@ -0,0 +1,56 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::query;
+use tracing::instrument;
+
+use super::Database;
+
+impl Database {
+    /// Add `address` to the tokens, or update it if it is already known.
+    ///
+    /// `None` values leave the stored ones unchanged, and a token stays
+    /// native once it has been saved as such.
+    #[instrument(skip(self))]
+    pub async fn save_token(
+        &self,
+        address: &str,
+        alias: Option<&str>,
+        denomination: Option<u8>,
+        is_native: bool,
+    ) -> Result<(), Error> {
+        let str = format!(
+            "INSERT INTO {}.tokens AS t(address, alias, denomination, is_native)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (address) DO UPDATE
+            SET alias = COALESCE($2, t.alias),
+                denomination = COALESCE($3, t.denomination),
+                is_native = t.is_native OR $4;",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .bind(alias)
+            .bind(denomination.map(i32::from))
+            .bind(is_native)
+            .execute(&*self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the known tokens, the native token first.
+    pub async fn get_tokens(&self) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT address, alias, denomination, is_native FROM {}.tokens ORDER BY is_native DESC, alias, address;",
+            self.network
+        );
+
+        query(&str)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
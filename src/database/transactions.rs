@@ -0,0 +1,943 @@
+use crate::{config::DecodePolicy, error::Error, utils};
+
+use namada_sdk::{
+    borsh::BorshSerializeExt,
+    tx::{data::TxType, Section, Signer, Tx},
+    types::hash::Hash,
+};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+use sqlx::{query, QueryBuilder, Transaction};
+use std::collections::HashMap;
+use tendermint::abci::Event;
+use tendermint_rpc::endpoint::block_results;
+use tracing::{debug, info, instrument, warn};
+
+use crate::{DB_SAVE_TXS_BATCH_SIZE, DB_SAVE_TXS_DURATION};
+
+use crate::decoder::{self, SideRow, TxContext, TxData};
+use crate::rewards;
+
+use metrics::histogram;
+
+use super::{BlockState, Database, IndexedTx, SaveOptions};
+
+impl Database {
+    /// Save all the transactions in txs, it is up to the caller to
+    /// call sqlx_tx.commit().await?; for the changes to take place in
+    /// database.
+    ///
+    /// Returns the wrapper transactions, the gas used by the decrypted ones and
+    /// the applied transactions, along with the code types of the decode
+    /// failures.
+    #[instrument(skip(txs, block_id, sqlx_tx, block_results, network, options))]
+    pub(super) async fn save_transactions<'a>(
+        txs: &[Vec<u8>],
+        block_id: &[u8],
+        block_height: u64,
+        block_results: &block_results::Response,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+        options: &SaveOptions,
+    ) -> Result<(Vec<IndexedTx>, Vec<String>), Error> {
+        // use for metrics
+        let instant = tokio::time::Instant::now();
+
+        if txs.is_empty() {
+            let labels = [
+                ("bulk_insert", "transactions".to_string()),
+                ("status", "Ok".to_string()),
+            ];
+
+            let dur = instant.elapsed();
+
+            histogram!(DB_SAVE_TXS_DURATION, dur.as_secs_f64() * 1000.0, &labels);
+            histogram!(DB_SAVE_TXS_BATCH_SIZE, 0.0, &labels);
+
+            return Ok(Default::default());
+        }
+
+        debug!(message = "Saving transactions");
+
+        // the wrappers of the previous block by the hash of their inner
+        // transaction, decrypted transactions are linked to them.
+        let wrappers = Self::previous_wrappers(block_height, sqlx_tx, network).await?;
+
+        let BlockTxs {
+            rows,
+            sections,
+            side_rows,
+            failures,
+            indexed,
+        } = decode_block_txs(
+            txs,
+            block_id,
+            block_height,
+            block_results,
+            &wrappers,
+            options.decode_policy,
+        )?;
+
+        // (hash, block_id, tx_index, compression, raw) only filled if
+        // raw transactions are stored, there is a row for every transaction.
+        let mut raw_values = Vec::new();
+
+        if options.store_raw_txs {
+            for (index, (row, t)) in rows.iter().zip(txs).enumerate() {
+                raw_values.push((
+                    row.hash.clone(),
+                    block_id.to_vec(),
+                    index as i32,
+                    options.raw_txs_compression.as_str(),
+                    utils::compress_raw_tx(options.raw_txs_compression, t)?,
+                ));
+            }
+        }
+
+        let num_transactions = rows.len();
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.transactions(
+                    hash, 
+                    block_id, 
+                    tx_type,
+                    wrapper_id,
+                    fee_amount_per_gas_unit,
+                    fee_token,
+                    gas_limit_multiplier,
+                    code,
+                    code_type,
+                    memo,
+                    data,
+                    return_code,
+                    gas_used,
+                    inner_hash
+                )",
+            network
+        ));
+
+        // bulk insert to speed-up this
+        // the limit for bind values in postgres is 65535 values, that means that
+        // to hit that limit a block would need to have:
+        // n_tx = 65535/14 = 4681
+        // being 14 the number of columns.
+        let res = query_builder
+            .push_values(rows, |mut b, row| {
+                b.push_bind(row.hash)
+                    .push_bind(block_id)
+                    .push_bind(row.tx_type)
+                    .push_bind(row.wrapper_id)
+                    .push_bind(row.fee_amount_per_gas_unit)
+                    .push_bind(row.fee_token)
+                    .push_bind(row.gas_limit_multiplier)
+                    .push_bind(row.code)
+                    .push_bind(row.code_type)
+                    .push_bind(row.memo)
+                    .push_bind(row.data)
+                    .push_bind(row.return_code)
+                    .push_bind(row.gas_used)
+                    .push_bind(row.inner_hash);
+            })
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await
+            .map(|_| ())
+            .map_err(Error::from);
+
+        let dur = instant.elapsed();
+
+        let mut status = "Ok".to_string();
+        if let Err(e) = &res {
+            status = e.to_string();
+        }
+
+        let labels = [
+            ("bulk_insert", "transactions".to_string()),
+            ("status", status),
+        ];
+
+        histogram!(DB_SAVE_TXS_DURATION, dur.as_secs_f64() * 1000.0, &labels);
+        histogram!(DB_SAVE_TXS_BATCH_SIZE, num_transactions as f64, &labels);
+
+        res?;
+
+        Self::save_tx_sections(sections, sqlx_tx, network).await?;
+        Self::save_raw_transactions(raw_values, sqlx_tx, network).await?;
+        let failed_code_types = Self::save_decode_failures(failures, sqlx_tx, network).await?;
+        Self::save_side_rows(side_rows, sqlx_tx, network).await?;
+
+        Ok((indexed, failed_code_types))
+    }
+
+    /// Returns the hash and fee token of the wrappers of the block before
+    /// `block_height`, by the hash of their inner transaction.
+    pub(super) async fn previous_wrappers<'a>(
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<HashMap<Vec<u8>, (Vec<u8>, Option<String>)>, Error> {
+        let rows = query(&format!(
+            "SELECT hash, inner_hash, fee_token FROM {0}.transactions
+            WHERE inner_hash IS NOT NULL
+            AND block_id IN (SELECT block_id FROM {0}.blocks WHERE header_height = $1);",
+            network
+        ))
+        .bind(block_height as i32 - 1)
+        .fetch_all(&mut *sqlx_tx)
+        .await?;
+
+        rows.iter()
+            .map(|row| -> Result<_, Error> {
+                Ok((
+                    row.try_get("inner_hash")?,
+                    (row.try_get("hash")?, row.try_get("fee_token")?),
+                ))
+            })
+            .collect()
+    }
+
+    /// Save the sections of the block transactions, it is up to the caller to
+    /// call sqlx_tx.commit().await?; for the changes to take place in
+    /// database.
+    #[instrument(skip(sections, sqlx_tx, network))]
+    async fn save_tx_sections<'a>(
+        sections: Vec<TxSectionRow>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        if sections.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving transaction sections");
+
+        // the limit for bind values in postgres is 65535, so sections are
+        // inserted by chunks of 65535/9 rows, being 9 the number of columns.
+        for chunk in sections.chunks(65535 / 9) {
+            let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+                "INSERT INTO {}.tx_sections(
+                    tx_hash,
+                    block_id,
+                    section_index,
+                    kind,
+                    hash,
+                    size,
+                    signer_address,
+                    signer_public_keys,
+                    signature_targets
+                )",
+                network
+            ));
+
+            query_builder
+                .push_values(
+                    chunk,
+                    |mut b,
+                     (
+                        tx_hash,
+                        block_id,
+                        section_index,
+                        kind,
+                        hash,
+                        size,
+                        signer_address,
+                        signer_public_keys,
+                        signature_targets,
+                    )| {
+                        b.push_bind(tx_hash)
+                            .push_bind(block_id)
+                            .push_bind(section_index)
+                            .push_bind(*kind)
+                            .push_bind(hash)
+                            .push_bind(size)
+                            .push_bind(signer_address)
+                            .push_bind(signer_public_keys)
+                            .push_bind(signature_targets);
+                    },
+                )
+                .push(" ON CONFLICT DO NOTHING")
+                .build()
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Save the raw bytes of the block transactions, it is up to the caller to
+    /// call sqlx_tx.commit().await?; for the changes to take place in
+    /// database.
+    #[instrument(skip(raw_txs, sqlx_tx, network))]
+    async fn save_raw_transactions<'a>(
+        raw_txs: Vec<(Vec<u8>, Vec<u8>, i32, &'static str, Vec<u8>)>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        if raw_txs.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving raw transactions");
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.raw_transactions(
+                    hash,
+                    block_id,
+                    tx_index,
+                    compression,
+                    raw
+            )",
+            network
+        ));
+
+        query_builder
+            .push_values(
+                raw_txs.into_iter(),
+                |mut b, (hash, block_id, tx_index, compression, raw)| {
+                    b.push_bind(hash)
+                        .push_bind(block_id)
+                        .push_bind(tx_index)
+                        .push_bind(compression)
+                        .push_bind(raw);
+                },
+            )
+            .push(" ON CONFLICT DO NOTHING")
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await
+            .map(|_| ())
+            .map_err(Error::from)
+    }
+
+    /// Save the results of a block along with the state it has been saved
+    /// with, the derived tables are rebuilt from them when redecoding. It is
+    /// up to the caller to call sqlx_tx.commit().await?; for the changes to
+    /// take place in database.
+    #[instrument(skip(block_id, block_results, state, sqlx_tx, network))]
+    pub(super) async fn save_raw_block_results<'a>(
+        block_id: &[u8],
+        block_height: u64,
+        block_results: &block_results::Response,
+        state: &BlockState,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        debug!("saving raw block results");
+
+        query(&format!(
+            "INSERT INTO {}.raw_block_results(block_id, height, results, state)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING;",
+            network
+        ))
+        .bind(block_id)
+        .bind(block_height as i32)
+        .bind(serde_json::to_value(block_results)?)
+        .bind(serde_json::to_value(state)?)
+        .execute(&mut *sqlx_tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Save the transactions that could not be decoded, it is up to the caller
+    /// to call sqlx_tx.commit().await?; for the changes to take place in
+    /// database, and to count the returned code types of the failures with
+    /// [Self::record_decode_failures] once committed.
+    #[instrument(skip(failures, sqlx_tx, network))]
+    pub(super) async fn save_decode_failures<'a>(
+        failures: Vec<DecodeFailureRow>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<Vec<String>, Error> {
+        if failures.is_empty() {
+            return Ok(vec![]);
+        }
+
+        debug!("saving decode failures");
+
+        let code_types: Vec<String> = failures
+            .iter()
+            .map(|f| f.3.clone().unwrap_or("unknown".to_string()))
+            .collect();
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.decode_failures(
+                    tx_hash,
+                    block_id,
+                    height,
+                    code_type,
+                    error,
+                    payload
+            )",
+            network
+        ));
+
+        query_builder
+            .push_values(
+                failures.into_iter(),
+                |mut b, (tx_hash, block_id, height, code_type, error, payload)| {
+                    b.push_bind(tx_hash)
+                        .push_bind(block_id)
+                        .push_bind(height)
+                        .push_bind(code_type)
+                        .push_bind(error)
+                        .push_bind(payload);
+                },
+            )
+            .push(" ON CONFLICT DO NOTHING")
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(code_types)
+    }
+
+    /// Save the rows produced by the transaction decoders in their side tables,
+    /// it is up to the caller to call sqlx_tx.commit().await?; for the changes
+    /// to take place in database.
+    #[instrument(skip(rows, sqlx_tx, network))]
+    pub(super) async fn save_side_rows<'a>(
+        rows: Vec<SideRow>,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        for row in rows {
+            // table and column names come from the decoders and can not be
+            // bound, so make sure they are plain identifiers.
+            if !is_sql_identifier(&row.table) || !row.values.keys().all(|c| is_sql_identifier(c)) {
+                return Err(Error::InvalidSideRow(row.table));
+            }
+
+            let columns = row.values.keys().cloned().collect::<Vec<_>>().join(", ");
+
+            // let postgres convert the json values into the column types.
+            let str = format!(
+                "INSERT INTO {0}.{1} ({2}) SELECT {2} FROM json_populate_record(NULL::{0}.{1}, $1);",
+                network, row.table, columns
+            );
+
+            query(&str)
+                .bind(serde_json::Value::Object(row.values))
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the sections of the transaction identified by hash, in the order
+    /// they appear in the transaction.
+    pub async fn get_tx_sections(&self, hash: &[u8]) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT * FROM {}.tx_sections WHERE tx_hash = $1 ORDER BY block_id, section_index;",
+            self.network
+        );
+
+        query(&str)
+            .bind(hash)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the most recent decode failures, by default the last 10.
+    pub async fn get_decode_failures(
+        &self,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT * FROM {}.decode_failures ORDER BY height DESC LIMIT {} OFFSET {};",
+            self.network,
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+// (tx_hash, block_id, section_index, kind, hash, size, signer_address, signer_public_keys, signature_targets)
+pub(super) type TxSectionRow = (
+    Vec<u8>,
+    Vec<u8>,
+    i32,
+    &'static str,
+    Vec<u8>,
+    i32,
+    Option<String>,
+    Option<Vec<String>>,
+    Option<Vec<Vec<u8>>>,
+);
+
+// (tx_hash, block_id, height, code_type, error, payload)
+pub(super) type DecodeFailureRow = (Vec<u8>, Vec<u8>, i32, Option<String>, String, Vec<u8>);
+
+/// A row of the `transactions` table, without its block.
+#[derive(Debug, Clone)]
+pub(super) struct TxRow {
+    pub(super) hash: Vec<u8>,
+    pub(super) tx_type: String,
+    pub(super) wrapper_id: Vec<u8>,
+    pub(super) fee_amount_per_gas_unit: Option<String>,
+    pub(super) fee_token: Option<String>,
+    pub(super) gas_limit_multiplier: Option<i64>,
+    pub(super) code: [u8; 32],
+    pub(super) code_type: String,
+    pub(super) memo: Vec<u8>,
+    pub(super) data: Option<serde_json::Value>,
+    pub(super) return_code: Option<i32>,
+    pub(super) gas_used: Option<i64>,
+    pub(super) inner_hash: Option<Vec<u8>>,
+}
+
+/// What is stored for the transactions of a block, and the transactions the
+/// derived tables are built from.
+#[derive(Debug, Default)]
+pub(super) struct BlockTxs {
+    /// A row for every transaction of the block, in order.
+    pub(super) rows: Vec<TxRow>,
+    pub(super) sections: Vec<TxSectionRow>,
+    /// Rows produced by the decoders for their side tables.
+    pub(super) side_rows: Vec<SideRow>,
+    /// Transactions that could not be decoded, only filled with the lenient
+    /// decode policy.
+    pub(super) failures: Vec<DecodeFailureRow>,
+    pub(super) indexed: Vec<IndexedTx>,
+}
+
+impl BlockTxs {
+    /// Returns the hash and fee token of the wrappers of the block, by the
+    /// hash of their inner transaction.
+    pub(super) fn wrappers(&self) -> HashMap<Vec<u8>, (Vec<u8>, Option<String>)> {
+        self.rows
+            .iter()
+            .filter_map(|row| {
+                let inner_hash = row.inner_hash.clone()?;
+                Some((inner_hash, (row.hash.clone(), row.fee_token.clone())))
+            })
+            .collect()
+    }
+}
+
+/// Decode the transactions of a block, `wrappers` being the wrappers of the
+/// previous block by the hash of their inner transaction.
+///
+/// This is shared by the indexing of the blocks and their redecoding, so both
+/// build the same rows.
+pub(super) fn decode_block_txs(
+    txs: &[Vec<u8>],
+    block_id: &[u8],
+    block_height: u64,
+    block_results: &block_results::Response,
+    wrappers: &HashMap<Vec<u8>, (Vec<u8>, Option<String>)>,
+    policy: DecodePolicy,
+) -> Result<BlockTxs, Error> {
+    let mut block_txs = BlockTxs::default();
+
+    for t in txs.iter() {
+        let tx = match Tx::try_from(t.as_slice()) {
+            Ok(tx) => tx,
+            Err(e) => {
+                let err = Error::InvalidTxData(e.to_string());
+                if policy == DecodePolicy::Strict {
+                    return Err(err);
+                }
+
+                // without a valid envelope the transaction is identified
+                // by the hash of its bytes, and kept with null data.
+                let hash_id = Hash::sha256(t).0.to_vec();
+                warn!(
+                    "Could not decode transaction {} at height {}: {}",
+                    hex::encode(&hash_id),
+                    block_height,
+                    err
+                );
+                block_txs.failures.push((
+                    hash_id.clone(),
+                    block_id.to_vec(),
+                    block_height as i32,
+                    None,
+                    err.to_string(),
+                    t.clone(),
+                ));
+                block_txs.rows.push(TxRow {
+                    hash: hash_id,
+                    tx_type: "Unknown".to_string(),
+                    wrapper_id: vec![],
+                    fee_amount_per_gas_unit: None,
+                    fee_token: None,
+                    gas_limit_multiplier: None,
+                    code: Default::default(),
+                    code_type: "none".to_string(),
+                    memo: vec![],
+                    data: None,
+                    return_code: None,
+                    gas_used: None,
+                    inner_hash: None,
+                });
+                continue;
+            }
+        };
+
+        let mut code = Default::default();
+        let mut code_type: String = "none".to_string();
+        let memo: Vec<u8> = tx.memo().unwrap_or_default();
+        let mut txid_wrapper: Vec<u8> = vec![];
+        let mut hash_id = tx.header_hash().to_vec();
+        let mut data_json: Option<serde_json::Value> = None;
+        let mut return_code: Option<i32> = None;
+        let mut gas_used: Option<i64> = None;
+
+        // Decrypted transaction give access to the raw data
+        if let TxType::Decrypted(..) = tx.header().tx_type {
+            // For unknown reason the header has to be updated before hashing it for its id (https://github.com/Zondax/namadexer/issues/23)
+            hash_id = tx.clone().update_header(TxType::Raw).header_hash().to_vec();
+            let hash_id_str = hex::encode(&hash_id);
+
+            // filter to get the matching event for hash_id
+            let matching_event = utils::tx_event(block_results, &hash_id_str);
+            gas_used = event_gas_used(matching_event);
+
+            // now for the event get its attribute and parse the return code
+            if let Some(event) = matching_event {
+                // Now, look for the "code" attribute in the found event
+                if let Some(code_attr) = event.attributes.iter().find(|attr| attr.key == "code") {
+                    // Parse the code value.
+                    // It could be possible to ignore the error by converting the result
+                    // to an Option<i32> but it is better to fail if the value is not a number.
+                    return_code = Some(code_attr.value.parse()?);
+                }
+            }
+
+            // wrappers are linked by the hash of their header with the raw
+            // type, the one of their inner transaction.
+            let (wrapper_hash, fee_token) = wrappers.get(&hash_id).cloned().unwrap_or_else(|| {
+                warn!("No wrapper found for transaction {}", hash_id_str);
+                Default::default()
+            });
+            txid_wrapper = wrapper_hash;
+
+            // the gas used by the inner transaction is accounted
+            // to the fees of its wrapper.
+            block_txs.indexed.push(IndexedTx::Decrypted {
+                fee_token,
+                gas_used,
+            });
+
+            code = tx
+                .get_section(tx.code_sechash())
+                .and_then(|s| s.code_sec())
+                .map(|s| s.code.hash().0)
+                .ok_or(Error::InvalidTxData("no code hash".into()))?;
+
+            let type_tx = decoder::code_type(&code);
+
+            let ctx = TxContext {
+                hash: &hash_id,
+                block_id,
+                height: block_height,
+                code_type: &type_tx,
+            };
+
+            // a transaction without result code can not be known to be
+            // applied, it fails like an undecodable transaction.
+            let applied = match return_code {
+                Some(code) => code == 0,
+                None => {
+                    let err = Error::InvalidTxData(format!(
+                        "no return code for transaction {}",
+                        hash_id_str
+                    ));
+                    if policy == DecodePolicy::Strict {
+                        return Err(err);
+                    }
+
+                    warn!("Could not decode {} transaction: {}", type_tx, err);
+                    block_txs.failures.push(decode_failure(&ctx, &err, &tx, t));
+                    false
+                }
+            };
+
+            // decode tx_transfer, tx_bond and tx_unbound to store the decoded data in their tables
+            // if the transaction has failed don't try to decode because the changes are not included and the data might not be correct
+            if applied {
+                code_type = type_tx.to_string();
+
+                info!("Saving {} transaction", type_tx);
+
+                let decoding = decode_applied_tx(&ctx, &tx, t, policy)?;
+
+                // the claimed amount of a reward claim is only known from its
+                // event, the claim is kept with an unknown amount otherwise.
+                if let Some(IndexedTx::Applied { data, .. }) = &decoding.applied {
+                    if matches!(data.as_ref(), TxData::ClaimRewards(_))
+                        && rewards::claimed_amount(block_results, &hash_id).is_none()
+                    {
+                        let err = Error::InvalidTxData(format!(
+                            "no claimed amount for transaction {}",
+                            hash_id_str
+                        ));
+                        if policy == DecodePolicy::Strict {
+                            return Err(err);
+                        }
+
+                        warn!("Could not decode {} transaction: {}", type_tx, err);
+                        block_txs.failures.push(decode_failure(&ctx, &err, &tx, t));
+                    }
+                }
+
+                data_json = decoding.data;
+                block_txs.side_rows.extend(decoding.side_rows);
+                block_txs.indexed.extend(decoding.applied);
+                block_txs.failures.extend(decoding.failure);
+            }
+        }
+
+        // values only set if transaction type is Wrapper
+        let mut fee_amount_per_gas_unit: Option<String> = None;
+        let mut fee_token: Option<String> = None;
+        let mut gas_limit_multiplier: Option<i64> = None;
+        let mut inner_hash: Option<Vec<u8>> = None;
+        if let TxType::Wrapper(txw) = tx.header().tx_type {
+            fee_amount_per_gas_unit = Some(txw.fee.amount_per_gas_unit.to_string_precise());
+            fee_token = Some(txw.fee.token.to_string());
+            let multiplier: u64 = txw.gas_limit.into();
+            // WARNING! converting into i64 might ended up changing the value but there is little
+            // chance that he goes higher than i64 max value
+            gas_limit_multiplier = Some(multiplier as i64);
+            gas_used = event_gas_used(utils::tx_event(block_results, &hex::encode(&hash_id)));
+            inner_hash = Some(tx.clone().update_header(TxType::Raw).header_hash().to_vec());
+
+            block_txs.indexed.push(IndexedTx::Wrapper {
+                hash: hash_id.clone(),
+                wrapper: txw,
+            });
+        }
+
+        for (index, section) in tx.sections.iter().enumerate() {
+            let (signer_address, signer_public_keys, signature_targets) = match section {
+                Section::Signature(sig) => {
+                    let targets: Vec<Vec<u8>> = sig.targets.iter().map(|h| h.0.to_vec()).collect();
+                    match &sig.signer {
+                        Signer::Address(addr) => (Some(addr.to_string()), None, Some(targets)),
+                        Signer::PubKeys(pks) => (
+                            None,
+                            Some(pks.iter().map(|pk| pk.to_string()).collect::<Vec<_>>()),
+                            Some(targets),
+                        ),
+                    }
+                }
+                _ => (None, None, None),
+            };
+
+            block_txs.sections.push((
+                hash_id.clone(),
+                block_id.to_vec(),
+                index as i32,
+                section_kind(section),
+                section.get_hash().0.to_vec(),
+                section.serialize_to_vec().len() as i32,
+                signer_address,
+                signer_public_keys,
+                signature_targets,
+            ));
+        }
+
+        block_txs.rows.push(TxRow {
+            hash: hash_id,
+            tx_type: utils::tx_type_name(&tx.header.tx_type),
+            wrapper_id: txid_wrapper,
+            fee_amount_per_gas_unit,
+            fee_token,
+            gas_limit_multiplier,
+            code,
+            code_type,
+            memo,
+            data: data_json,
+            return_code,
+            gas_used,
+            inner_hash,
+        });
+    }
+
+    Ok(block_txs)
+}
+
+/// Returns the name used to identify a transaction section kind
+/// in the `tx_sections` table.
+fn section_kind(section: &Section) -> &'static str {
+    match section {
+        Section::Data(_) => "data",
+        Section::ExtraData(_) => "extra_data",
+        Section::Code(_) => "code",
+        Section::Signature(_) => "signature",
+        Section::Ciphertext(_) => "ciphertext",
+        Section::MaspTx(_) => "masp_tx",
+        Section::MaspBuilder(_) => "masp_builder",
+        Section::Header(_) => "header",
+    }
+}
+
+/// Returns the gas used by a transaction as reported by its event.
+fn event_gas_used(event: Option<&Event>) -> Option<i64> {
+    event?
+        .attributes
+        .iter()
+        .find(|attr| attr.key == "gas_used")
+        .and_then(|attr| attr.value.parse().ok())
+}
+
+/// What is stored for an applied transaction once decoded.
+#[derive(Debug, Default)]
+struct AppliedTxDecoding {
+    /// The json stored in `transactions.data`, if it could be decoded.
+    data: Option<serde_json::Value>,
+    side_rows: Vec<SideRow>,
+    /// The transaction the derived tables are built from, if decoded.
+    applied: Option<IndexedTx>,
+    /// Only set with the lenient policy, if it could not be decoded.
+    failure: Option<DecodeFailureRow>,
+}
+
+/// Decode an applied transaction with the registered decoders, `raw` being
+/// its borsh bytes. The derived tables are built from the data returned by
+/// the decoder, a transaction that can not be decoded fails with an error with
+/// the strict policy, and is kept with null data along with its failure
+/// otherwise.
+fn decode_applied_tx(
+    ctx: &TxContext,
+    tx: &Tx,
+    raw: &[u8],
+    policy: DecodePolicy,
+) -> Result<AppliedTxDecoding, Error> {
+    match decoder::decode_tx(ctx, &tx.data().unwrap_or_default()) {
+        Ok(decoded) => Ok(AppliedTxDecoding {
+            data: Some(decoded.data),
+            side_rows: decoded.side_rows,
+            applied: Some(IndexedTx::Applied {
+                hash: ctx.hash.to_vec(),
+                code_type: ctx.code_type.to_string(),
+                data: Box::new(decoded.tx_data),
+                tx: Box::new(tx.clone()),
+            }),
+            failure: None,
+        }),
+        Err(e) if policy == DecodePolicy::Strict => Err(e),
+        Err(e) => {
+            warn!(
+                "Could not decode {} transaction {}: {}",
+                ctx.code_type,
+                hex::encode(ctx.hash),
+                e
+            );
+
+            Ok(AppliedTxDecoding {
+                failure: Some(decode_failure(ctx, &e, tx, raw)),
+                ..Default::default()
+            })
+        }
+    }
+}
+
+/// Returns the `decode_failures` row of a transaction that could not be
+/// decoded, its payload being its data or `raw` if it has none.
+fn decode_failure(ctx: &TxContext, error: &Error, tx: &Tx, raw: &[u8]) -> DecodeFailureRow {
+    (
+        ctx.hash.to_vec(),
+        ctx.block_id.to_vec(),
+        ctx.height as i32,
+        Some(ctx.code_type.to_string()),
+        error.to_string(),
+        tx.data().unwrap_or_else(|| raw.to_vec()),
+    )
+}
+
+/// Returns true if `name` can be used as is as a table or column name.
+pub(super) fn is_sql_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{address, ADDRESS};
+    use namada_sdk::tx::Data;
+    use serde_json::json;
+
+    fn ctx(code_type: &str) -> TxContext {
+        TxContext {
+            hash: &[0xab, 0xcd],
+            block_id: &[0x01],
+            height: 2,
+            code_type,
+        }
+    }
+
+    fn tx_with_data(data: Vec<u8>) -> Tx {
+        let mut tx = Tx::from_type(TxType::Raw);
+        tx.set_data(Data::new(data));
+        tx
+    }
+
+    #[test]
+    fn test_decode_applied_tx() {
+        let address = address(ADDRESS);
+        let tx = tx_with_data(address.serialize_to_vec());
+        let ctx = ctx("tx_unjail_validator");
+
+        for policy in [DecodePolicy::Strict, DecodePolicy::Lenient] {
+            let decoding = decode_applied_tx(&ctx, &tx, &[], policy).unwrap();
+
+            assert_eq!(decoding.data, Some(json!(address.to_string())));
+            assert!(decoding.failure.is_none());
+            assert!(matches!(
+                decoding.applied,
+                Some(IndexedTx::Applied { hash, code_type, data, .. })
+                    if hash == [0xab, 0xcd]
+                        && code_type == "tx_unjail_validator"
+                        && matches!(data.as_ref(), TxData::UnjailValidator(a) if *a == address)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_decode_applied_tx_lenient() {
+        let tx = tx_with_data(vec![1, 2, 3]);
+        let ctx = ctx("tx_unjail_validator");
+
+        assert!(decode_applied_tx(&ctx, &tx, &[], DecodePolicy::Strict).is_err());
+
+        // the transaction is kept with null data and no derived rows
+        let decoding = decode_applied_tx(&ctx, &tx, &[], DecodePolicy::Lenient).unwrap();
+        assert_eq!(decoding.data, None);
+        assert!(decoding.side_rows.is_empty());
+        assert!(decoding.applied.is_none());
+
+        let (tx_hash, block_id, height, code_type, _, payload) = decoding.failure.unwrap();
+        assert_eq!(tx_hash, vec![0xab, 0xcd]);
+        assert_eq!(block_id, vec![0x01]);
+        assert_eq!(height, 2);
+        assert_eq!(code_type.as_deref(), Some("tx_unjail_validator"));
+        assert_eq!(payload, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_decode_failure_without_data() {
+        let tx = Tx::from_type(TxType::Raw);
+        let err = Error::InvalidTxData("no return code".into());
+
+        let (_, _, _, _, error, payload) = decode_failure(&ctx("tx_bond"), &err, &tx, &[4, 5]);
+        assert_eq!(error, err.to_string());
+        assert_eq!(payload, vec![4, 5]);
+    }
+}
@@ -0,0 +1,90 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+use sqlx::{query, QueryBuilder, Transaction};
+use tendermint::block::Block;
+use tracing::{debug, instrument};
+
+use super::{BlockState, Database};
+
+impl Database {
+    /// Save the validator set of a block if it changed since the last saved one,
+    /// it is up to the caller to call sqlx_tx.commit().await?; for the changes
+    /// to take place in database.
+    #[instrument(skip(block, state, sqlx_tx, network))]
+    pub(super) async fn save_validator_set<'a>(
+        block: &Block,
+        state: &BlockState,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let Some(validator_set) = &state.validator_set else {
+            return Ok(());
+        };
+
+        let height = block.header.height.value() as i32;
+        let validators_hash = block.header.validators_hash.as_bytes().to_vec();
+
+        // the set is read again when the indexer restarts
+        let last_hash: Option<Vec<u8>> = query(&format!(
+            "SELECT validators_hash FROM {}.validator_sets WHERE height < $1 ORDER BY height DESC LIMIT 1;",
+            network
+        ))
+        .bind(height)
+        .fetch_optional(&mut *sqlx_tx)
+        .await?
+        .map(|row| row.try_get("validators_hash"))
+        .transpose()?;
+
+        if last_hash.as_ref() == Some(&validators_hash) || validator_set.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving validator set");
+
+        let mut query_builder: QueryBuilder<_> = QueryBuilder::new(format!(
+            "INSERT INTO {}.validator_sets(height, validators_hash, consensus_address, voting_power)",
+            network
+        ));
+
+        query_builder
+            .push_values(validator_set, |mut b, (consensus_address, voting_power)| {
+                b.push_bind(height)
+                    .push_bind(&validators_hash)
+                    .push_bind(consensus_address)
+                    .push_bind(voting_power);
+            })
+            .push(" ON CONFLICT DO NOTHING")
+            .build()
+            .execute(&mut *sqlx_tx)
+            .await?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the validator set at `height`, the largest voting power first.
+    ///
+    /// Validators are identified by their tendermint address and by the Namada
+    /// address of their consensus key when it is known.
+    pub async fn get_validator_set(&self, height: i32) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT s.height, s.validators_hash, s.consensus_address, s.voting_power, k.validator
+            FROM {0}.validator_sets s
+            LEFT JOIN LATERAL (
+                SELECT validator FROM {0}.validator_consensus_keys
+                WHERE consensus_address = s.consensus_address ORDER BY height DESC LIMIT 1
+            ) k ON true
+            WHERE s.height = (SELECT MAX(height) FROM {0}.validator_sets WHERE height <= $1)
+            ORDER BY s.voting_power DESC, s.consensus_address;",
+            self.network
+        );
+
+        query(&str)
+            .bind(height)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
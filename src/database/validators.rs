@@ -0,0 +1,316 @@
+use crate::error::Error;
+
+use sqlx::postgres::PgRow as Row;
+use sqlx::{query, Transaction};
+use tracing::{debug, instrument};
+
+use crate::validators::{self, ValidatorUpdate};
+
+use super::{BlockState, Database, IndexedTx};
+
+impl Database {
+    /// Record the consensus keys of the validator set read along with the
+    /// block, and add its validators to the registry, it is up to the caller to
+    /// call sqlx_tx.commit().await?; for the changes to take place in database.
+    ///
+    /// This maps the tendermint addresses of the genesis validators, and of the
+    /// validators whose keys were set before the first indexed block, to their
+    /// Namada addresses. Keys already recorded for a validator are skipped.
+    #[instrument(skip(state, sqlx_tx, network))]
+    pub(super) async fn save_validator_set_keys<'a>(
+        state: &BlockState,
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let Some(consensus_keys) = &state.validator_consensus_keys else {
+            return Ok(());
+        };
+
+        debug!("saving validator set consensus keys");
+
+        let height = block_height as i32;
+
+        let consensus_key_query = format!(
+            "INSERT INTO {0}.validator_consensus_keys(consensus_address, consensus_key, validator, height, tx_hash)
+            SELECT $1, $2, $3, $4, NULL
+            WHERE NOT EXISTS (
+                SELECT 1 FROM {0}.validator_consensus_keys WHERE consensus_address = $1 AND validator = $3
+            );",
+            network
+        );
+
+        let validator_query = format!(
+            "INSERT INTO {0}.validators(address, consensus_key, last_change_height)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (address) DO UPDATE
+            SET consensus_key = EXCLUDED.consensus_key
+            WHERE {0}.validators.consensus_key IS NULL;",
+            network
+        );
+
+        for (consensus_address, consensus_key, validator) in consensus_keys {
+            query(&consensus_key_query)
+                .bind(consensus_address)
+                .bind(consensus_key)
+                .bind(validator)
+                .bind(height)
+                .execute(&mut *sqlx_tx)
+                .await?;
+
+            query(&validator_query)
+                .bind(validator)
+                .bind(consensus_key)
+                .bind(height)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Update the validators with the lifecycle transactions of a block and
+    /// record their changes, it is up to the caller to call
+    /// sqlx_tx.commit().await?; for the changes to take place in database.
+    ///
+    /// Validators created at genesis get a row when they are first seen in a
+    /// validator set, without the fields that were never changed.
+    #[instrument(skip(txs, sqlx_tx, network))]
+    pub(super) async fn save_validators<'a>(
+        txs: &[IndexedTx],
+        block_height: u64,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let changes = validators::validator_changes(txs)?;
+
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        debug!("saving validators");
+
+        let height = block_height as i32;
+
+        let history_query = format!(
+            "INSERT INTO {}.validator_changes(address, kind, data, height, tx_hash)
+            VALUES ($1, $2, $3, $4, $5);",
+            network
+        );
+
+        let consensus_key_query = format!(
+            "INSERT INTO {}.validator_consensus_keys(consensus_address, consensus_key, validator, height, tx_hash)
+            VALUES ($1, $2, $3, $4, $5);",
+            network
+        );
+
+        for change in changes {
+            Self::save_validator_update(&change.address, change.update, height, sqlx_tx, network)
+                .await?;
+
+            if let Some(consensus_key) = change.consensus_key {
+                query(&consensus_key_query)
+                    .bind(consensus_key.address)
+                    .bind(consensus_key.key)
+                    .bind(&change.address)
+                    .bind(height)
+                    .bind(&change.tx_hash)
+                    .execute(&mut *sqlx_tx)
+                    .await?;
+            }
+
+            query(&history_query)
+                .bind(change.address)
+                .bind(change.kind)
+                .bind(change.data)
+                .bind(height)
+                .bind(change.tx_hash)
+                .execute(&mut *sqlx_tx)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply `update` to the validator `address`, it is up to the caller to
+    /// call sqlx_tx.commit().await?; for the changes to take place in database.
+    async fn save_validator_update<'a>(
+        address: &str,
+        update: ValidatorUpdate,
+        height: i32,
+        sqlx_tx: &mut Transaction<'a, sqlx::Postgres>,
+        network: &str,
+    ) -> Result<(), Error> {
+        let create_query = format!(
+            "INSERT INTO {0}.validators(address, consensus_key, commission_rate, max_commission_rate_change,
+                email, description, website, discord_handle, avatar, state, height, last_change_height)
+            VALUES ($1, $2, $3::NUMERIC, $4::NUMERIC, $5, NULLIF($6, ''), NULLIF($7, ''), NULLIF($8, ''), NULLIF($9, ''), 'active', $10, $10)
+            ON CONFLICT (address) DO UPDATE
+            SET consensus_key = EXCLUDED.consensus_key, commission_rate = EXCLUDED.commission_rate,
+                max_commission_rate_change = EXCLUDED.max_commission_rate_change, email = EXCLUDED.email,
+                description = EXCLUDED.description, website = EXCLUDED.website,
+                discord_handle = EXCLUDED.discord_handle, avatar = EXCLUDED.avatar, state = EXCLUDED.state,
+                height = EXCLUDED.height, last_change_height = EXCLUDED.last_change_height;",
+            network
+        );
+
+        // a NULL parameter leaves the column unchanged, an empty string removes it.
+        let update_query = format!(
+            "INSERT INTO {0}.validators AS v(address, consensus_key, commission_rate, email, description,
+                website, discord_handle, avatar, state, last_change_height)
+            VALUES ($1, $2, $3::NUMERIC, NULLIF($4, ''), NULLIF($5, ''), NULLIF($6, ''), NULLIF($7, ''), NULLIF($8, ''), COALESCE($9, 'active'), $10)
+            ON CONFLICT (address) DO UPDATE
+            SET consensus_key = COALESCE($2, v.consensus_key),
+                commission_rate = COALESCE($3::NUMERIC, v.commission_rate),
+                email = CASE WHEN $4 IS NULL THEN v.email ELSE NULLIF($4, '') END,
+                description = CASE WHEN $5 IS NULL THEN v.description ELSE NULLIF($5, '') END,
+                website = CASE WHEN $6 IS NULL THEN v.website ELSE NULLIF($6, '') END,
+                discord_handle = CASE WHEN $7 IS NULL THEN v.discord_handle ELSE NULLIF($7, '') END,
+                avatar = CASE WHEN $8 IS NULL THEN v.avatar ELSE NULLIF($8, '') END,
+                state = COALESCE($9, v.state),
+                last_change_height = EXCLUDED.last_change_height;",
+            network
+        );
+
+        match update {
+            ValidatorUpdate::Create {
+                consensus_key,
+                commission_rate,
+                max_commission_rate_change,
+                metadata,
+            } => {
+                query(&create_query)
+                    .bind(address)
+                    .bind(consensus_key)
+                    .bind(commission_rate)
+                    .bind(max_commission_rate_change)
+                    .bind(metadata.email)
+                    .bind(metadata.description)
+                    .bind(metadata.website)
+                    .bind(metadata.discord_handle)
+                    .bind(metadata.avatar)
+                    .bind(height)
+                    .execute(&mut *sqlx_tx)
+                    .await?;
+            }
+            ValidatorUpdate::Update {
+                consensus_key,
+                commission_rate,
+                metadata,
+                state,
+            } => {
+                query(&update_query)
+                    .bind(address)
+                    .bind(consensus_key)
+                    .bind(commission_rate)
+                    .bind(metadata.email)
+                    .bind(metadata.description)
+                    .bind(metadata.website)
+                    .bind(metadata.discord_handle)
+                    .bind(metadata.avatar)
+                    .bind(state)
+                    .bind(height)
+                    .execute(&mut *sqlx_tx)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the validators ordered by address, by default the first 10.
+    pub async fn get_validators(
+        &self,
+        num: Option<&i32>,
+        offset: Option<&i32>,
+    ) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "{} ORDER BY address LIMIT {} OFFSET {};",
+            validators_select_query(&self.network),
+            num.unwrap_or(&10),
+            offset.unwrap_or(&0)
+        );
+
+        query(&str)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the validator identified by its Namada `address`.
+    pub async fn get_validator(&self, address: &str) -> Result<Option<Row>, Error> {
+        let str = format!(
+            "{} WHERE address = $1;",
+            validators_select_query(&self.network)
+        );
+
+        query(&str)
+            .bind(address)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the changes of the validator `address`, in the order they happened.
+    pub async fn get_validator_changes(&self, address: &str) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT kind, data, height, tx_hash FROM {}.validator_changes WHERE address = $1 ORDER BY id;",
+            self.network
+        );
+
+        query(&str)
+            .bind(address)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the Namada address of the validator whose consensus key has
+    /// `consensus_address` as tendermint address.
+    pub async fn get_validator_by_consensus_address(
+        &self,
+        consensus_address: &[u8],
+    ) -> Result<Option<Row>, Error> {
+        let str = format!(
+            "SELECT validator FROM {}.validator_consensus_keys WHERE consensus_address = $1
+            ORDER BY height DESC LIMIT 1;",
+            self.network
+        );
+
+        query(&str)
+            .bind(consensus_address)
+            .fetch_optional(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+
+    #[instrument(skip(self))]
+    /// Returns the consensus keys of `validator` and the height they were set, in
+    /// the order they were set.
+    pub async fn get_validator_consensus_keys(&self, validator: &str) -> Result<Vec<Row>, Error> {
+        let str = format!(
+            "SELECT consensus_address, consensus_key, height, tx_hash FROM {}.validator_consensus_keys
+            WHERE validator = $1 ORDER BY height;",
+            self.network
+        );
+
+        query(&str)
+            .bind(validator)
+            .fetch_all(&*self.pool)
+            .await
+            .map_err(Error::from)
+    }
+}
+
+// Selects the validators with their rates as decimal strings.
+fn validators_select_query(network: &str) -> String {
+    format!(
+        "SELECT address, consensus_key, commission_rate::TEXT AS commission_rate,
+        max_commission_rate_change::TEXT AS max_commission_rate_change, email, description, website,
+        discord_handle, avatar, state, height, last_change_height FROM {network}.validators"
+    )
+}
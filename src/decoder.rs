@@ -0,0 +1,478 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use namada_sdk::types::key::common::PublicKey;
+use namada_sdk::{
+    account::{InitAccount, UpdateAccount},
+    borsh::BorshDeserialize,
+    governance::{InitProposalData, VoteProposalData},
+    tx::data::{
+        pgf::UpdateStewardCommission,
+        pos::{
+            BecomeValidator, Bond, CommissionChange, ConsensusKeyChange, MetaDataChange,
+            Redelegation, Unbond, Withdraw,
+        },
+    },
+    types::{address::Address, eth_bridge_pool::PendingTransfer, token},
+};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use tracing::info;
+
+use crate::error::Error;
+use crate::CHECKSUMS;
+
+// Decoders used by the indexer, stored as a global like the checksums
+// so library users can register their own decoders before indexing.
+static DECODERS: Lazy<RwLock<DecoderRegistry>> =
+    Lazy::new(|| RwLock::new(DecoderRegistry::default()));
+
+/// Information about the transaction being decoded.
+#[derive(Debug, Clone, Copy)]
+pub struct TxContext<'a> {
+    /// The hash that identifies the transaction.
+    pub hash: &'a [u8],
+    /// The block the transaction belongs to.
+    pub block_id: &'a [u8],
+    /// Height of the block the transaction belongs to.
+    pub height: u64,
+    /// The transaction type as registered along with its decoder or found in
+    /// the checksums, e.g. `tx_transfer`.
+    pub code_type: &'a str,
+}
+
+/// A row to be inserted in a side table along with the transaction.
+///
+/// Side tables must live in the network schema and have a `tx_hash BYTEA`
+/// column, which is filled by [`SideRow::new`]. This is used to replace the
+/// rows of a transaction when it gets redecoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SideRow {
+    /// The table name, without the network schema.
+    pub table: String,
+    /// Values by column name, converted to the column type by postgres.
+    pub values: Map<String, Value>,
+}
+
+impl SideRow {
+    pub fn new(table: impl Into<String>, ctx: &TxContext) -> Self {
+        let mut values = Map::new();
+        values.insert("tx_hash".to_string(), bytea(ctx.hash));
+
+        Self {
+            table: table.into(),
+            values,
+        }
+    }
+
+    /// Set the value of `column`, byte values should be converted using [`bytea`].
+    pub fn with(mut self, column: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.values.insert(column.into(), value.into());
+        self
+    }
+}
+
+/// Returns the json representation postgres expects for a BYTEA value.
+pub fn bytea(bytes: &[u8]) -> Value {
+    Value::String(format!("\\x{}", hex::encode(bytes)))
+}
+
+/// The result of decoding the data of a transaction.
+#[derive(Debug, Clone)]
+pub struct DecodedTx {
+    /// Json representation of the data, stored in `transactions.data`.
+    pub data: Value,
+    /// Rows to be stored in side tables.
+    pub side_rows: Vec<SideRow>,
+    /// The data the derived tables are built from.
+    pub tx_data: TxData,
+}
+
+impl DecodedTx {
+    /// A transaction no table is derived from, decoded as `data`.
+    pub fn new(data: Value) -> Self {
+        Self {
+            data,
+            side_rows: vec![],
+            tx_data: TxData::Other,
+        }
+    }
+}
+
+impl Default for DecodedTx {
+    fn default() -> Self {
+        Self::new(json!(null))
+    }
+}
+
+/// Decodes the data section of a transaction type.
+///
+/// Decoders are registered by transaction name along with the code hash of the
+/// transaction using [`register_decoder`].
+pub trait TxDecoder: Send + Sync {
+    fn decode(&self, ctx: &TxContext, data: &[u8]) -> Result<DecodedTx, Error>;
+
+    /// The side tables this decoder writes to, the rows of a transaction
+    /// in these tables are deleted before it is redecoded.
+    fn side_tables(&self) -> Vec<String> {
+        vec![]
+    }
+}
+
+/// Decoder for transactions whose data is a borsh encoded `T`, the data is
+/// stored as the json serialization of `T` and the derived tables are built
+/// from the [`TxData`] `T` is turned into.
+pub struct BorshDecoder<T> {
+    tx_data: fn(T) -> TxData,
+}
+
+impl<T> BorshDecoder<T> {
+    pub fn new(tx_data: fn(T) -> TxData) -> Self {
+        Self { tx_data }
+    }
+}
+
+impl<T> TxDecoder for BorshDecoder<T>
+where
+    T: BorshDeserialize + Serialize,
+{
+    fn decode(&self, _ctx: &TxContext, data: &[u8]) -> Result<DecodedTx, Error> {
+        let decoded = T::try_from_slice(data)?;
+
+        Ok(DecodedTx {
+            data: serde_json::to_value(&decoded)?,
+            side_rows: vec![],
+            tx_data: (self.tx_data)(decoded),
+        })
+    }
+}
+
+/// Decoder that stores the data as an hex string.
+pub struct HexDecoder;
+
+impl TxDecoder for HexDecoder {
+    fn decode(&self, ctx: &TxContext, data: &[u8]) -> Result<DecodedTx, Error> {
+        info!("we do not handle {} transaction yet", ctx.code_type);
+        Ok(DecodedTx::new(serde_json::to_value(hex::encode(data))?))
+    }
+}
+
+/// Transaction decoders by transaction name, and the names of the
+/// transactions registered by code hash.
+pub struct DecoderRegistry {
+    decoders: HashMap<String, Arc<dyn TxDecoder>>,
+    code_types: HashMap<[u8; 32], String>,
+}
+
+impl DecoderRegistry {
+    /// Creates a registry without any decoder.
+    pub fn empty() -> Self {
+        Self {
+            decoders: HashMap::new(),
+            code_types: HashMap::new(),
+        }
+    }
+
+    /// Register `decoder` for transactions named `name`, returning the
+    /// decoder it replaces if any.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        decoder: impl TxDecoder + 'static,
+    ) -> Option<Arc<dyn TxDecoder>> {
+        self.decoders.insert(name.into(), Arc::new(decoder))
+    }
+
+    /// Register `decoder` for transactions named `name` whose code hash is
+    /// `code_hash`, returning the decoder it replaces if any.
+    pub fn register_code(
+        &mut self,
+        name: impl Into<String>,
+        code_hash: [u8; 32],
+        decoder: impl TxDecoder + 'static,
+    ) -> Option<Arc<dyn TxDecoder>> {
+        let name = name.into();
+        self.code_types.insert(code_hash, name.clone());
+        self.register(name, decoder)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn TxDecoder>> {
+        self.decoders.get(name).cloned()
+    }
+
+    /// Returns the name of the transactions registered with `code_hash`.
+    pub fn code_type(&self, code_hash: &[u8; 32]) -> Option<String> {
+        self.code_types.get(code_hash).cloned()
+    }
+}
+
+impl Default for DecoderRegistry {
+    /// Creates a registry with the decoders for the namada transactions.
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        registry.register(
+            "tx_transfer",
+            BorshDecoder::<token::Transfer>::new(TxData::Transfer),
+        );
+        registry.register("tx_bond", BorshDecoder::<Bond>::new(TxData::Bond));
+        registry.register("tx_unbond", BorshDecoder::<Unbond>::new(TxData::Unbond));
+        // this is an ethereum transaction
+        // Only TransferToEthereum type is supported at the moment by namada and us.
+        registry.register(
+            "tx_bridge_pool",
+            BorshDecoder::<PendingTransfer>::new(TxData::BridgePool),
+        );
+        registry.register(
+            "tx_vote_proposal",
+            BorshDecoder::<VoteProposalData>::new(TxData::VoteProposal),
+        );
+        // only check that data is a valid publicKey
+        // otherwise this transaction must not make it into
+        // the database.
+        registry.register(
+            "tx_reveal_pk",
+            BorshDecoder::<PublicKey>::new(TxData::RevealPk),
+        );
+        registry.register(
+            "tx_resign_steward",
+            BorshDecoder::<Address>::new(TxData::ResignSteward),
+        );
+        registry.register(
+            "tx_update_steward_commission",
+            BorshDecoder::<UpdateStewardCommission>::new(TxData::UpdateStewardCommission),
+        );
+        // later accounts could be updated using
+        // tx_update_account, however there is not way
+        // so far to link those transactions to this.
+        registry.register(
+            "tx_init_account",
+            BorshDecoder::<InitAccount>::new(TxData::InitAccount),
+        );
+        registry.register(
+            "tx_update_account",
+            BorshDecoder::<UpdateAccount>::new(TxData::UpdateAccount),
+        );
+        registry.register("tx_ibc", HexDecoder);
+        registry.register(
+            "tx_become_validator",
+            BorshDecoder::<BecomeValidator>::new(|data| TxData::BecomeValidator(Box::new(data))),
+        );
+        registry.register(
+            "tx_change_consensus_key",
+            BorshDecoder::<ConsensusKeyChange>::new(TxData::ChangeConsensusKey),
+        );
+        registry.register(
+            "tx_change_validator_commission",
+            BorshDecoder::<CommissionChange>::new(TxData::ChangeValidatorCommission),
+        );
+        registry.register(
+            "tx_change_validator_metadata",
+            BorshDecoder::<MetaDataChange>::new(TxData::ChangeValidatorMetadata),
+        );
+        registry.register(
+            "tx_claim_rewards",
+            BorshDecoder::<Withdraw>::new(TxData::ClaimRewards),
+        );
+        registry.register(
+            "tx_deactivate_validator",
+            BorshDecoder::<Address>::new(TxData::DeactivateValidator),
+        );
+        registry.register(
+            "tx_init_proposal",
+            BorshDecoder::<InitProposalData>::new(TxData::InitProposal),
+        );
+        registry.register(
+            "tx_reactivate_validator",
+            BorshDecoder::<Address>::new(TxData::ReactivateValidator),
+        );
+        registry.register(
+            "tx_unjail_validator",
+            BorshDecoder::<Address>::new(TxData::UnjailValidator),
+        );
+        registry.register(
+            "tx_redelegate",
+            BorshDecoder::<Redelegation>::new(TxData::Redelegate),
+        );
+        registry.register(
+            "tx_withdraw",
+            BorshDecoder::<Withdraw>::new(TxData::Withdraw),
+        );
+
+        registry
+    }
+}
+
+/// Register `decoder` for transactions named `name` whose code hash is
+/// `code_hash` in the decoders used by the indexer, replacing the existing one
+/// if any.
+///
+/// This allows decoding custom wasm transactions without listing them in the
+/// checksums file.
+pub fn register_decoder(
+    name: impl Into<String>,
+    code_hash: [u8; 32],
+    decoder: impl TxDecoder + 'static,
+) {
+    DECODERS
+        .write()
+        .expect("Decoders lock poisoned")
+        .register_code(name, code_hash, decoder);
+}
+
+/// Returns the type of the transactions whose code hash is `code_hash`, as
+/// registered along with their decoder or found in the checksums, `unknown`
+/// otherwise.
+pub(crate) fn code_type(code_hash: &[u8; 32]) -> String {
+    let registered = DECODERS
+        .read()
+        .expect("Decoders lock poisoned")
+        .code_type(code_hash);
+
+    registered
+        .or_else(|| CHECKSUMS.get(&hex::encode(code_hash)).cloned())
+        .unwrap_or("unknown".to_string())
+}
+
+/// Returns the decoder registered for transactions named `name`.
+pub(crate) fn get_decoder(name: &str) -> Option<Arc<dyn TxDecoder>> {
+    DECODERS.read().expect("Decoders lock poisoned").get(name)
+}
+
+/// Decode the data section of a transaction using the decoder registered for
+/// its type. Transactions without a decoder are decoded as `null`.
+pub(crate) fn decode_tx(ctx: &TxContext, data: &[u8]) -> Result<DecodedTx, Error> {
+    match get_decoder(ctx.code_type) {
+        Some(decoder) => decoder.decode(ctx, data),
+        None => Ok(DecodedTx::default()),
+    }
+}
+
+/// The data of the namada transactions the derived tables (balance changes,
+/// delegations, proposals, validators, ...) are built from.
+///
+/// It is returned by the decoder registered for the transaction type, so the
+/// derived tables follow the registered decoders and are rebuilt from the new
+/// data when transactions get redecoded.
+#[derive(Debug, Clone)]
+pub enum TxData {
+    Transfer(token::Transfer),
+    Bond(Bond),
+    Unbond(Unbond),
+    Redelegate(Redelegation),
+    Withdraw(Withdraw),
+    ClaimRewards(Withdraw),
+    InitProposal(InitProposalData),
+    VoteProposal(VoteProposalData),
+    RevealPk(PublicKey),
+    InitAccount(InitAccount),
+    UpdateAccount(UpdateAccount),
+    BecomeValidator(Box<BecomeValidator>),
+    ChangeConsensusKey(ConsensusKeyChange),
+    ChangeValidatorCommission(CommissionChange),
+    ChangeValidatorMetadata(MetaDataChange),
+    DeactivateValidator(Address),
+    ReactivateValidator(Address),
+    UnjailValidator(Address),
+    ResignSteward(Address),
+    UpdateStewardCommission(UpdateStewardCommission),
+    BridgePool(PendingTransfer),
+    /// A transaction no table is derived from.
+    Other,
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_sdk::borsh::BorshSerializeExt;
+
+    use super::*;
+    use crate::test_utils::{address, ADDRESS};
+
+    struct LenDecoder;
+
+    impl TxDecoder for LenDecoder {
+        fn decode(&self, ctx: &TxContext, data: &[u8]) -> Result<DecodedTx, Error> {
+            Ok(DecodedTx {
+                data: json!({ "len": data.len() }),
+                side_rows: vec![SideRow::new("custom", ctx).with("len", data.len())],
+                tx_data: TxData::Other,
+            })
+        }
+    }
+
+    fn ctx(code_type: &str) -> TxContext {
+        TxContext {
+            hash: &[0xab, 0xcd],
+            block_id: &[],
+            height: 1,
+            code_type,
+        }
+    }
+
+    #[test]
+    fn test_registry_custom_decoder() {
+        let mut registry = DecoderRegistry::default();
+        let ctx = ctx("tx_custom");
+
+        assert!(registry.get("tx_custom").is_none());
+        assert!(registry.register("tx_custom", LenDecoder).is_none());
+
+        let decoded = registry
+            .get("tx_custom")
+            .unwrap()
+            .decode(&ctx, &[1, 2, 3])
+            .unwrap();
+        assert_eq!(decoded.data, json!({ "len": 3 }));
+        assert_eq!(decoded.side_rows[0].table, "custom");
+        assert_eq!(decoded.side_rows[0].values["tx_hash"], json!("\\xabcd"));
+        assert_eq!(decoded.side_rows[0].values["len"], json!(3));
+
+        // registering again replaces the decoder, the others are kept
+        assert!(registry.register("tx_custom", HexDecoder).is_some());
+        assert_eq!(
+            registry
+                .get("tx_custom")
+                .unwrap()
+                .decode(&ctx, &[1, 2, 3])
+                .unwrap()
+                .data,
+            json!("010203")
+        );
+        assert!(registry.get("tx_transfer").is_some());
+    }
+
+    #[test]
+    fn test_register_decoder() {
+        // the name is only used by this test, as the decoders are global
+        let ctx = ctx("tx_test_register_decoder");
+
+        let code_hash = [0xfe; 32];
+
+        assert_eq!(decode_tx(&ctx, &[1, 2, 3]).unwrap().data, json!(null));
+
+        register_decoder("tx_test_register_decoder", code_hash, LenDecoder);
+
+        assert_eq!(
+            decode_tx(&ctx, &[1, 2, 3]).unwrap().data,
+            json!({ "len": 3 })
+        );
+        // the code hash is known without being in the checksums
+        assert_eq!(code_type(&code_hash), "tx_test_register_decoder");
+    }
+
+    #[test]
+    fn test_borsh_decoder_tx_data() {
+        let address = address(ADDRESS);
+        let registry = DecoderRegistry::default();
+
+        let decoded = registry
+            .get("tx_unjail_validator")
+            .unwrap()
+            .decode(&ctx("tx_unjail_validator"), &address.serialize_to_vec())
+            .unwrap();
+
+        assert_eq!(decoded.data, json!(address.to_string()));
+        assert!(matches!(decoded.tx_data, TxData::UnjailValidator(a) if a == address));
+    }
+}
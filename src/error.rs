@@ -10,10 +10,11 @@ use tokio::task::JoinError;
 use config::ConfigError;
 use opentelemetry_api::metrics::MetricsError;
 use tendermint::Error as TError;
-use tendermint_rpc::endpoint::block_results;
 use tendermint_rpc::Error as TRpcError;
 use tokio::sync::mpsc::error::SendError;
 
+use crate::indexer::BlockInfo;
+
 #[derive(Debug, ThisError)]
 pub enum Error {
     #[error("Invalid Block data")]
@@ -53,6 +54,12 @@ pub enum Error {
     SerdeJsonError(#[from] serde_json::Error),
     #[error("Invalid checksum data")]
     InvalidChecksum,
+    #[error("Invalid side table row (table: {0})")]
+    InvalidSideRow(String),
+    #[error("Node query error: {0}")]
+    NodeQueryError(String),
+    #[error("Missing block state: {0}")]
+    MissingBlockState(String),
     #[error("Unknow error: {0}")]
     Generic(Box<dyn StdError + Send>),
     #[error("ParseInt error")]
@@ -63,8 +70,8 @@ pub enum Error {
     Timeout(#[from] tokio::time::error::Elapsed),
 }
 
-impl From<SendError<(tendermint::Block, block_results::Response)>> for Error {
-    fn from(_: SendError<(tendermint::Block, block_results::Response)>) -> Self {
+impl From<SendError<BlockInfo>> for Error {
+    fn from(_: SendError<BlockInfo>) -> Self {
         Self::SendError
     }
 }
@@ -0,0 +1,204 @@
+use std::collections::BTreeMap;
+
+use crate::database::IndexedTx;
+
+/// The fees of the wrapper transactions of a block paying in the same token.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TokenFees {
+    /// Fee per gas unit of every wrapper, in the token smallest unit.
+    pub fees_per_gas: Vec<String>,
+    /// Gas limit of every wrapper, in the same order as `fees_per_gas`.
+    pub gas_limits: Vec<i64>,
+}
+
+/// The gas used by the decrypted transactions of a block whose wrappers
+/// pay in the same token.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct GasUsed {
+    /// Height of the block of their wrappers.
+    pub height: i32,
+    pub token: String,
+    pub gas: i64,
+}
+
+/// Returns the fees of the wrapper transactions of a block by fee token.
+pub(crate) fn block_fees(txs: &[IndexedTx]) -> BTreeMap<String, TokenFees> {
+    let mut fees: BTreeMap<String, TokenFees> = BTreeMap::new();
+
+    for tx in txs {
+        let IndexedTx::Wrapper { wrapper, .. } = tx else {
+            continue;
+        };
+
+        let gas_limit: u64 = wrapper.gas_limit.clone().into();
+        let token_fees = fees.entry(wrapper.fee.token.to_string()).or_default();
+
+        token_fees.fees_per_gas.push(
+            wrapper
+                .fee
+                .amount_per_gas_unit
+                .amount()
+                .raw_amount()
+                .to_string(),
+        );
+        token_fees.gas_limits.push(gas_limit as i64);
+    }
+
+    fees
+}
+
+/// Returns the gas used by the decrypted transactions of the block at
+/// `block_height` by the fee token of their wrapper.
+///
+/// Wrappers are part of the block before the one of their decrypted
+/// transaction, so the gas used is accounted to the previous block.
+pub(crate) fn gas_used(txs: &[IndexedTx], block_height: u64) -> Vec<GasUsed> {
+    let mut used: BTreeMap<String, i64> = BTreeMap::new();
+
+    for tx in txs {
+        let IndexedTx::Decrypted {
+            fee_token: Some(token),
+            gas_used: Some(gas),
+        } = tx
+        else {
+            continue;
+        };
+
+        *used.entry(token.clone()).or_default() += gas;
+    }
+
+    used.into_iter()
+        .map(|(token, gas)| GasUsed {
+            height: block_height as i32 - 1,
+            token,
+            gas,
+        })
+        .collect()
+}
+
+/// Returns the heights of the blocks whose fees are changed by the block at
+/// `block_height`, whose epochs have to be rolled up again.
+pub(crate) fn changed_heights(block_height: u64, gas_used: &[GasUsed]) -> Vec<i32> {
+    let mut heights = vec![block_height as i32];
+
+    for used in gas_used {
+        if !heights.contains(&used.height) {
+            heights.push(used.height);
+        }
+    }
+
+    heights
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use namada_sdk::tx::data::{Fee, GasLimit, WrapperTx};
+    use namada_sdk::types::{
+        key::common::PublicKey,
+        storage::Epoch,
+        token::{self, DenominatedAmount, Denomination},
+    };
+
+    use super::*;
+    use crate::test_utils::{address, NATIVE, THIRD_ADDRESS as OTHER};
+
+    fn wrapper(token: &str, fee_per_gas: u64, gas_limit: u64) -> IndexedTx {
+        let fee = Fee {
+            amount_per_gas_unit: DenominatedAmount::new(
+                token::Amount::from_u64(fee_per_gas),
+                Denomination(0),
+            ),
+            token: address(token),
+        };
+        let pk = PublicKey::from_str(
+            "tpknam1qpvxvenxvenxvenxvenxvenxvenxvenxvenxvenxvenxvenxvenxv5rt7eh",
+        )
+        .unwrap();
+
+        IndexedTx::Wrapper {
+            hash: vec![],
+            wrapper: Box::new(WrapperTx::new(
+                fee,
+                pk,
+                Epoch(0),
+                GasLimit::from(gas_limit),
+                None,
+            )),
+        }
+    }
+
+    fn decrypted(token: Option<&str>, gas_used: Option<i64>) -> IndexedTx {
+        IndexedTx::Decrypted {
+            fee_token: token.map(str::to_string),
+            gas_used,
+        }
+    }
+
+    #[test]
+    fn test_block_fees() {
+        let txs = vec![
+            wrapper(NATIVE, 2, 100),
+            decrypted(Some(NATIVE), Some(10)),
+            wrapper(OTHER, 5, 20),
+            wrapper(NATIVE, 3, 50),
+        ];
+
+        assert_eq!(
+            block_fees(&txs),
+            BTreeMap::from([
+                (
+                    NATIVE.to_string(),
+                    TokenFees {
+                        fees_per_gas: vec!["2".to_string(), "3".to_string()],
+                        gas_limits: vec![100, 50],
+                    }
+                ),
+                (
+                    OTHER.to_string(),
+                    TokenFees {
+                        fees_per_gas: vec!["5".to_string()],
+                        gas_limits: vec![20],
+                    }
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_gas_used() {
+        let txs = vec![
+            decrypted(Some(NATIVE), Some(10)),
+            wrapper(NATIVE, 2, 100),
+            decrypted(Some(OTHER), Some(7)),
+            decrypted(Some(NATIVE), Some(5)),
+            // the wrapper or the gas used is unknown
+            decrypted(None, Some(3)),
+            decrypted(Some(NATIVE), None),
+        ];
+
+        let used = gas_used(&txs, 10);
+
+        // the gas is accounted to the block of the wrappers
+        assert_eq!(
+            used,
+            vec![
+                GasUsed {
+                    height: 9,
+                    token: NATIVE.to_string(),
+                    gas: 15,
+                },
+                GasUsed {
+                    height: 9,
+                    token: OTHER.to_string(),
+                    gas: 7,
+                },
+            ]
+        );
+
+        // the epochs of both blocks are rolled up again
+        assert_eq!(changed_heights(10, &used), vec![10, 9]);
+        assert_eq!(changed_heights(10, &[]), vec![10]);
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use namada_sdk::{borsh::BorshDeserialize, governance::InitProposalData, tx::Tx};
+use serde_json::Value;
+
+use crate::{database::IndexedTx, decoder::TxData, error::Error};
+
+/// A proposal submitted by an applied `tx_init_proposal`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NewProposal {
+    pub id: i64,
+    pub author: String,
+    pub content_hash: Vec<u8>,
+    /// The proposal content, `None` if the section it is stored in is missing.
+    pub content: Option<Value>,
+    /// The proposal type, e.g. `Default` or `PGFSteward`.
+    pub kind: String,
+    /// The data attached to the proposal type, like the stewards to add or remove.
+    pub kind_data: Value,
+    pub voting_start_epoch: i32,
+    pub voting_end_epoch: i32,
+    pub grace_epoch: i32,
+    pub tx_hash: Vec<u8>,
+}
+
+/// A vote cast by an applied `tx_vote_proposal`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NewVote {
+    pub proposal_id: i64,
+    pub voter: String,
+    /// One of `yay`, `nay` or `abstain`.
+    pub vote: &'static str,
+    pub tx_hash: Vec<u8>,
+}
+
+/// The proposals and votes of a block.
+#[derive(Debug, Default)]
+pub(crate) struct BlockGovernance {
+    pub proposals: Vec<NewProposal>,
+    pub votes: Vec<NewVote>,
+}
+
+impl BlockGovernance {
+    /// Returns the proposals and votes of the transactions of a block, in
+    /// the order they have been applied.
+    pub fn from_txs(txs: &[IndexedTx]) -> Result<Self, Error> {
+        let mut governance = Self::default();
+
+        for indexed in txs {
+            let IndexedTx::Applied { hash, data, tx, .. } = indexed else {
+                continue;
+            };
+
+            match data.as_ref() {
+                TxData::InitProposal(proposal) => {
+                    let kind_data = serde_json::to_value(&proposal.r#type)?;
+
+                    governance.proposals.push(NewProposal {
+                        id: proposal.id as i64,
+                        author: proposal.author.to_string(),
+                        content_hash: proposal.content.0.to_vec(),
+                        content: proposal_content(tx, proposal),
+                        kind: variant_name(&kind_data),
+                        kind_data,
+                        voting_start_epoch: proposal.voting_start_epoch.0 as i32,
+                        voting_end_epoch: proposal.voting_end_epoch.0 as i32,
+                        grace_epoch: proposal.grace_epoch.0 as i32,
+                        tx_hash: hash.clone(),
+                    });
+                }
+                TxData::VoteProposal(vote) => {
+                    governance.votes.push(NewVote {
+                        proposal_id: vote.id as i64,
+                        voter: vote.voter.to_string(),
+                        vote: if vote.vote.is_yay() {
+                            "yay"
+                        } else if vote.vote.is_nay() {
+                            "nay"
+                        } else {
+                            "abstain"
+                        },
+                        tx_hash: hash.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Ok(governance)
+    }
+}
+
+/// Returns the voting power of every voter of the proposal `proposal_id`,
+/// from the stake behind each of the votes stored by the ledger, as voter
+/// and raw amount: a validator votes with its stake and a delegator with its
+/// bond to every validator it delegates to.
+pub(crate) fn voting_powers(
+    proposal_id: i64,
+    stakes: &[(String, String)],
+) -> Result<Vec<(i64, String, String)>, Error> {
+    let mut powers: BTreeMap<&str, u128> = BTreeMap::new();
+
+    for (voter, amount) in stakes {
+        *powers.entry(voter.as_str()).or_default() += amount.parse::<u128>()?;
+    }
+
+    Ok(powers
+        .into_iter()
+        .map(|(voter, power)| (proposal_id, voter.to_string(), power.to_string()))
+        .collect())
+}
+
+// The content is a borsh encoded map stored in the extra data section
+// whose hash is part of the proposal data.
+fn proposal_content(tx: &Tx, proposal: &InitProposalData) -> Option<Value> {
+    let bytes = tx
+        .get_section(&proposal.content)
+        .and_then(|s| s.extra_data_sec())
+        .and_then(|s| s.code.id())?;
+
+    let content = BTreeMap::<String, String>::try_from_slice(&bytes).ok()?;
+
+    serde_json::to_value(content).ok()
+}
+
+// Enums are serialized as `"Variant"` or `{ "Variant": data }`.
+fn variant_name(value: &Value) -> String {
+    match value {
+        Value::String(name) => name.clone(),
+        Value::Object(map) => map.keys().next().cloned().unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_sdk::governance::{storage::vote::ProposalVote, VoteProposalData};
+
+    use super::*;
+    use crate::test_utils::{address, ADDRESS as VALIDATOR, OTHER_ADDRESS as DELEGATOR};
+
+    fn vote(voter: &str, vote: ProposalVote) -> VoteProposalData {
+        VoteProposalData {
+            id: 7,
+            vote,
+            voter: address(voter),
+            // a validator voting for itself or delegators voting for their
+            // validator, which doesn't tell whether the voter is a validator.
+            delegations: vec![address(VALIDATOR)],
+        }
+    }
+
+    #[test]
+    fn test_votes() {
+        let txs = vec![
+            IndexedTx::applied("tx_vote_proposal", &[1], vote(VALIDATOR, ProposalVote::Yay)),
+            IndexedTx::Decrypted {
+                fee_token: None,
+                gas_used: None,
+            },
+            IndexedTx::applied("tx_vote_proposal", &[2], vote(DELEGATOR, ProposalVote::Nay)),
+            IndexedTx::applied(
+                "tx_vote_proposal",
+                &[3],
+                vote(DELEGATOR, ProposalVote::Abstain),
+            ),
+        ];
+
+        let governance = BlockGovernance::from_txs(&txs).unwrap();
+        assert!(governance.proposals.is_empty());
+
+        assert_eq!(
+            governance.votes,
+            vec![
+                NewVote {
+                    proposal_id: 7,
+                    voter: VALIDATOR.to_string(),
+                    vote: "yay",
+                    tx_hash: vec![1],
+                },
+                NewVote {
+                    proposal_id: 7,
+                    voter: DELEGATOR.to_string(),
+                    vote: "nay",
+                    tx_hash: vec![2],
+                },
+                NewVote {
+                    proposal_id: 7,
+                    voter: DELEGATOR.to_string(),
+                    vote: "abstain",
+                    tx_hash: vec![3],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_voting_powers() {
+        // the delegator votes with its bonds to two validators
+        let stakes = vec![
+            (VALIDATOR.to_string(), "1000".to_string()),
+            (DELEGATOR.to_string(), "30".to_string()),
+            (DELEGATOR.to_string(), "20".to_string()),
+        ];
+
+        assert_eq!(
+            voting_powers(7, &stakes).unwrap(),
+            vec![
+                (7, DELEGATOR.to_string(), "50".to_string()),
+                (7, VALIDATOR.to_string(), "1000".to_string()),
+            ]
+        );
+
+        let invalid = vec![(VALIDATOR.to_string(), "x".to_string())];
+        assert!(voting_powers(7, &invalid).is_err());
+    }
+
+    #[test]
+    fn test_variant_name() {
+        assert_eq!(variant_name(&Value::String("Default".into())), "Default");
+        assert_eq!(
+            variant_name(&serde_json::json!({ "PGFSteward": [] })),
+            "PGFSteward"
+        );
+        assert_eq!(variant_name(&Value::Null), "");
+    }
+}
@@ -1,7 +1,23 @@
 use crate::config::IndexerConfig;
+use crate::database::BlockState;
+use crate::governance;
+use crate::stewards;
+use crate::validators;
 use futures::stream::StreamExt;
 use futures_util::pin_mut;
 use futures_util::Stream;
+use namada_sdk::borsh::BorshDeserialize;
+use namada_sdk::governance::parameters::GovernanceParameters;
+use namada_sdk::governance::storage::proposal::StorageProposal;
+use namada_sdk::governance::utils::Vote;
+use namada_sdk::proof_of_stake::parameters::PosParams;
+use namada_sdk::token::storage_key::{balance_prefix, is_any_token_balance_key};
+use namada_sdk::types::{
+    address::Address,
+    key::common::PublicKey,
+    storage::{Epoch, PrefixValue},
+    token,
+};
 use std::process;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
@@ -10,8 +26,9 @@ use std::time::Duration;
 use tendermint::block::Block;
 use tendermint::block::Height;
 use tendermint::genesis::Genesis;
+use tendermint::Hash;
 use tendermint_rpc::endpoint::block_results;
-use tendermint_rpc::{self, Client, HttpClient};
+use tendermint_rpc::{self, Client, HttpClient, Paging};
 use tokio::sync::mpsc::Receiver;
 use tokio::sync::mpsc::Sender;
 use tokio::task::JoinHandle;
@@ -25,6 +42,13 @@ use super::error::Error;
 // Time to wait between unsuccesfull calls to http.get_block
 const WAIT_FOR_BLOCK: u64 = 10;
 
+// Time to wait between two updates of the statistics once they are
+// up to date with the indexed blocks.
+const STATS_UPDATE_INTERVAL: u64 = 60;
+
+// Alias of the native token, unless another one is configured.
+const NATIVE_TOKEN_ALIAS: &str = "NAM";
+
 // Max number of queued blocks in channel.
 // this can be adjusted for optimal performance, however
 // either http request or database_queries are both slow
@@ -32,10 +56,27 @@ const WAIT_FOR_BLOCK: u64 = 10;
 const MAX_BLOCKS_IN_CHANNEL: usize = 100;
 
 // Block info required to be saved
-type BlockInfo = (Block, block_results::Response);
+pub(crate) type BlockInfo = (Block, block_results::Response, BlockState);
+
+// Values read once from the node when starting, used to
+// build the state of every block.
+#[derive(Debug, Clone, Default)]
+struct ChainParams {
+    native_token: Option<Address>,
+    withdrawable_epoch_offset: Option<u64>,
+    min_proposal_fund: Option<String>,
+    /// Only read when indexing from the first block.
+    initial_balances: Option<Vec<(String, String, String)>>,
+}
 
-#[instrument(skip(client))]
-async fn get_block(block_height: u32, chain_name: &str, client: &HttpClient) -> BlockInfo {
+#[instrument(skip(params, client))]
+async fn get_block(
+    block_height: u32,
+    chain_name: &str,
+    params: &ChainParams,
+    last_validators_hash: Option<Hash>,
+    client: &HttpClient,
+) -> Result<BlockInfo, Error> {
     loop {
         let height = Height::from(block_height);
         tracing::trace!(message = "Requesting block: ", block_height);
@@ -64,7 +105,21 @@ async fn get_block(block_height: u32, chain_name: &str, client: &HttpClient) ->
                 let block_results = get_block_results(height, client).await;
 
                 if let Ok(br) = block_results {
-                    return (resp.block, br);
+                    // unlike the block, the state of a committed block won't
+                    // become available by asking again, e.g. on a pruned node.
+                    let state =
+                        get_block_state(&resp.block, &br, params, last_validators_hash, client)
+                            .await
+                            .map_err(|e| {
+                                tracing::error!(
+                                    "Failed to get the state of block {}. (REASON : {})",
+                                    block_height,
+                                    e
+                                );
+                                e
+                            })?;
+
+                    return Ok((resp.block, br, state));
                 }
             }
 
@@ -149,15 +204,411 @@ async fn get_block_results(
     }
 }
 
+/// Read from the node state what is needed to save the block but is not part of
+/// it, failures are logged and the corresponding values left out, except for the
+/// voting powers of the proposals ending in the block, which fail.
+///
+/// The validator set is only read when its hash differs from
+/// `last_validators_hash`, the one of the previous block. The values read once
+/// when starting are copied from `params`.
+#[instrument(
+    name = "Indexer::block_state",
+    skip(block, block_results, params, client)
+)]
+async fn get_block_state(
+    block: &Block,
+    block_results: &block_results::Response,
+    params: &ChainParams,
+    last_validators_hash: Option<Hash>,
+    client: &HttpClient,
+) -> Result<BlockState, Error> {
+    let height = block.header.height.value() as u32;
+
+    let mut state = BlockState {
+        native_token: params.native_token.as_ref().map(|t| t.to_string()),
+        withdrawable_epoch_offset: params.withdrawable_epoch_offset,
+        min_proposal_fund: params.min_proposal_fund.clone(),
+        ..Default::default()
+    };
+
+    if height == 1 {
+        state.initial_balances = params.initial_balances.clone();
+    }
+
+    if last_validators_hash != Some(block.header.validators_hash) {
+        match get_validator_set(height, client).await {
+            Ok(validators) => state.validator_set = Some(validators),
+            Err(e) => warn!(
+                "Failed to get the validator set at height {}. (REASON : {})",
+                height, e
+            ),
+        }
+
+        if let Some(validators) = &state.validator_set {
+            match get_consensus_keys(validators, height, client).await {
+                Ok(consensus_keys) => state.validator_consensus_keys = Some(consensus_keys),
+                Err(e) => warn!(
+                    "Failed to get the consensus keys of the validator set at height {}. (REASON : {})",
+                    height, e
+                ),
+            }
+        }
+    }
+
+    match get_epoch(height, client).await {
+        Ok(epoch) => state.epoch = epoch,
+        Err(e) => warn!(
+            "Failed to get the epoch at height {}. (REASON : {})",
+            height, e
+        ),
+    }
+
+    let proposals = stewards::proposal_results(block_results);
+
+    if !proposals.is_empty() {
+        let mut voting_powers = vec![];
+
+        for (proposal_id, _) in proposals {
+            voting_powers.extend(get_voting_powers(proposal_id, height, client).await?);
+        }
+
+        state.voting_powers = Some(voting_powers);
+    }
+
+    Ok(state)
+}
+
+// Returns the owner, token and raw amount of the balances of tokens once
+// block_height was committed.
+async fn get_balances(
+    tokens: &[Address],
+    block_height: u32,
+    client: &HttpClient,
+) -> Result<Vec<(String, String, String)>, Error> {
+    let mut balances = vec![];
+
+    for token in tokens {
+        let path = format!("/shell/prefix/{}", balance_prefix(token));
+
+        let response = client
+            .abci_query(Some(path), vec![], Some(Height::from(block_height)), false)
+            .await?;
+
+        if response.code.is_err() {
+            return Err(Error::NodeQueryError(response.log));
+        }
+
+        // nothing is returned for tokens without any balance
+        if response.value.is_empty() {
+            continue;
+        }
+
+        for PrefixValue { key, value } in Vec::<PrefixValue>::try_from_slice(&response.value)? {
+            let Some([_, owner]) = is_any_token_balance_key(&key) else {
+                continue;
+            };
+
+            let amount = token::Amount::try_from_slice(&value)?;
+
+            balances.push((
+                owner.to_string(),
+                token.to_string(),
+                amount.raw_amount().to_string(),
+            ));
+        }
+    }
+
+    Ok(balances)
+}
+
+// Returns the tendermint address and voting power of the validators
+// of the block at block_height.
+async fn get_validator_set(
+    block_height: u32,
+    client: &HttpClient,
+) -> Result<Vec<(Vec<u8>, i64)>, Error> {
+    let response = client
+        .validators(Height::from(block_height), Paging::All)
+        .await?;
+
+    Ok(response
+        .validators
+        .iter()
+        .map(|v| (v.address.as_bytes().to_vec(), v.power.value() as i64))
+        .collect())
+}
+
+// Returns the tendermint address, consensus key and Namada address of the
+// validators, once block_height was committed.
+async fn get_consensus_keys(
+    validator_set: &[(Vec<u8>, i64)],
+    block_height: u32,
+    client: &HttpClient,
+) -> Result<Vec<(Vec<u8>, String, String)>, Error> {
+    let height = Some(Height::from(block_height));
+    let mut consensus_keys = vec![];
+
+    for (consensus_address, _) in validator_set {
+        let path = format!(
+            "/vp/pos/validator_by_tm_addr/{}",
+            hex::encode_upper(consensus_address)
+        );
+        let response = client.abci_query(Some(path), vec![], height, false).await?;
+
+        if response.code.is_err() {
+            return Err(Error::NodeQueryError(response.log));
+        }
+
+        let Some(validator) = Option::<Address>::try_from_slice(&response.value)? else {
+            continue;
+        };
+
+        let path = format!("/vp/pos/validator/consensus_key/{}", validator);
+        let response = client.abci_query(Some(path), vec![], height, false).await?;
+
+        if response.code.is_err() {
+            return Err(Error::NodeQueryError(response.log));
+        }
+
+        let Some(key) = Option::<PublicKey>::try_from_slice(&response.value)? else {
+            continue;
+        };
+
+        consensus_keys.extend(validators::validator_set_key(
+            consensus_address,
+            &validator,
+            &key,
+        )?);
+    }
+
+    Ok(consensus_keys)
+}
+
+// Returns the voter and voting power of the votes of the proposal proposal_id,
+// as the stake they had bonded at its voting end epoch, once block_height was
+// committed.
+async fn get_voting_powers(
+    proposal_id: i64,
+    block_height: u32,
+    client: &HttpClient,
+) -> Result<Vec<(i64, String, String)>, Error> {
+    let height = Some(Height::from(block_height));
+
+    let path = format!("/vp/governance/proposal/{}", proposal_id);
+    let response = client.abci_query(Some(path), vec![], height, false).await?;
+
+    if response.code.is_err() {
+        return Err(Error::NodeQueryError(response.log));
+    }
+
+    let proposal = Option::<StorageProposal>::try_from_slice(&response.value)?
+        .ok_or_else(|| Error::NodeQueryError(format!("proposal {} not found", proposal_id)))?;
+    let epoch = proposal.voting_end_epoch;
+
+    let path = format!("/vp/governance/proposal/{}/votes", proposal_id);
+    let response = client.abci_query(Some(path), vec![], height, false).await?;
+
+    if response.code.is_err() {
+        return Err(Error::NodeQueryError(response.log));
+    }
+
+    let mut stakes = vec![];
+
+    for vote in Vec::<Vote>::try_from_slice(&response.value)? {
+        // validators vote with their whole stake, delegators with their bond
+        let path = if vote.validator == vote.delegator {
+            format!("/vp/pos/validator/stake/{}/{}", vote.validator, epoch)
+        } else {
+            format!(
+                "/vp/pos/bond/{}/{}/{}",
+                vote.delegator, vote.validator, epoch
+            )
+        };
+
+        let response = client.abci_query(Some(path), vec![], height, false).await?;
+
+        if response.code.is_err() {
+            return Err(Error::NodeQueryError(response.log));
+        }
+
+        let stake = if vote.validator == vote.delegator {
+            Option::<token::Amount>::try_from_slice(&response.value)?.unwrap_or_default()
+        } else {
+            token::Amount::try_from_slice(&response.value)?
+        };
+
+        stakes.push((vote.delegator.to_string(), stake.raw_amount().to_string()));
+    }
+
+    governance::voting_powers(proposal_id, &stakes)
+}
+
+// Returns the epoch of the block at block_height.
+async fn get_epoch(block_height: u32, client: &HttpClient) -> Result<Option<u64>, Error> {
+    let path = format!("/shell/epoch_at_height/{}", block_height);
+
+    let response = client.abci_query(Some(path), vec![], None, false).await?;
+
+    if response.code.is_err() {
+        return Err(Error::NodeQueryError(response.log));
+    }
+
+    let epoch = Option::<Epoch>::try_from_slice(&response.value)?;
+
+    Ok(epoch.map(|e| e.0))
+}
+
+// Returns the proof of stake parameters.
+async fn get_pos_params(client: &HttpClient) -> Result<PosParams, Error> {
+    let response = client
+        .abci_query(Some("/vp/pos/pos_params".to_string()), vec![], None, false)
+        .await?;
+
+    if response.code.is_err() {
+        return Err(Error::NodeQueryError(response.log));
+    }
+
+    Ok(PosParams::try_from_slice(&response.value)?)
+}
+
+// Returns the amount locked by the proposals.
+async fn get_min_proposal_fund(client: &HttpClient) -> Result<token::Amount, Error> {
+    let response = client
+        .abci_query(
+            Some("/vp/governance/parameters".to_string()),
+            vec![],
+            None,
+            false,
+        )
+        .await?;
+
+    if response.code.is_err() {
+        return Err(Error::NodeQueryError(response.log));
+    }
+
+    Ok(GovernanceParameters::try_from_slice(&response.value)?.min_proposal_fund)
+}
+
+// Returns the address of the native token.
+async fn get_native_token(client: &HttpClient) -> Result<Address, Error> {
+    let response = client
+        .abci_query(Some("/shell/native_token".to_string()), vec![], None, false)
+        .await?;
+
+    if response.code.is_err() {
+        return Err(Error::NodeQueryError(response.log));
+    }
+
+    Ok(Address::try_from_slice(&response.value)?)
+}
+
+// Returns the address of the native token, asking the node again until it
+// answers like for the blocks, the balance changes can't be saved without it.
+async fn wait_native_token(client: &HttpClient) -> Address {
+    loop {
+        match get_native_token(client).await {
+            Ok(native_token) => return native_token,
+            Err(e) => {
+                warn!(
+                    "Failed to get the native token. Trying again in {} seconds. (REASON : {})",
+                    WAIT_FOR_BLOCK, e
+                );
+                tokio::time::sleep(Duration::from_secs(WAIT_FOR_BLOCK)).await;
+            }
+        }
+    }
+}
+
+// Returns the number of decimal places of token, if it has one.
+async fn get_denomination(token: &Address, client: &HttpClient) -> Result<Option<u8>, Error> {
+    let path = format!("/vp/token/denomination/{}", token);
+
+    let response = client.abci_query(Some(path), vec![], None, false).await?;
+
+    if response.code.is_err() {
+        return Err(Error::NodeQueryError(response.log));
+    }
+
+    let denomination = Option::<token::Denomination>::try_from_slice(&response.value)?;
+
+    Ok(denomination.map(|d| d.0))
+}
+
+// Save the native token and the configured tokens in the tokens table,
+// the denomination of the tokens not configured with one is read from the node.
+async fn save_tokens(
+    db: &Database,
+    config: &IndexerConfig,
+    native_token: Option<&Address>,
+    client: &HttpClient,
+) -> Result<(), Error> {
+    if let Some(native_token) = native_token {
+        let denomination = match get_denomination(native_token, client).await {
+            Ok(denomination) => denomination,
+            Err(e) => {
+                warn!(
+                    "Failed to get the denomination of the native token. (REASON : {})",
+                    e
+                );
+                None
+            }
+        };
+
+        db.save_token(
+            &native_token.to_string(),
+            Some(NATIVE_TOKEN_ALIAS),
+            denomination,
+            true,
+        )
+        .await?;
+    }
+
+    for token in &config.tokens {
+        let mut denomination = token.denomination;
+
+        if denomination.is_none() {
+            match Address::decode(&token.address) {
+                Ok(address) => match get_denomination(&address, client).await {
+                    Ok(d) => denomination = d,
+                    Err(e) => warn!(
+                        "Failed to get the denomination of {}. (REASON : {})",
+                        token.alias, e
+                    ),
+                },
+                Err(e) => warn!("Invalid address for token {}: {}", token.alias, e),
+            }
+        }
+
+        db.save_token(&token.address, Some(&token.alias), denomination, false)
+            .await?;
+    }
+
+    Ok(())
+}
+
 #[allow(clippy::let_with_type_underscore)]
-#[instrument(name = "Indexer::blocks_stream", skip(client, block))]
+#[instrument(name = "Indexer::blocks_stream", skip(client, block, params))]
 fn blocks_stream<'a>(
     block: u64,
     chain_name: &'a str,
+    params: &'a ChainParams,
     client: &'a HttpClient,
-) -> impl Stream<Item = BlockInfo> + 'a {
-    futures::stream::iter(block..)
-        .then(move |i| async move { get_block(i as u32, chain_name, client).await })
+) -> impl Stream<Item = Result<BlockInfo, Error>> + 'a {
+    // blocks are fetched one after the other, which allows comparing the
+    // validators hash of a block with the previous one, the stream ends
+    // after the first error.
+    futures::stream::unfold(Some((block, None)), move |next| async move {
+        let (i, last_validators_hash) = next?;
+
+        match get_block(i as u32, chain_name, params, last_validators_hash, client).await {
+            Ok(block_info) => {
+                let validators_hash = block_info.0.header.validators_hash;
+
+                Some((Ok(block_info), Some((i + 1, Some(validators_hash)))))
+            }
+            Err(e) => Some((Err(e), None)),
+        }
+    })
 }
 
 /// Start the indexer service blocking current thread.
@@ -209,6 +660,55 @@ pub async fn start_indexing(
         client = HttpClient::new("https://api.zondax.ch/nam/node/testnet")?;
     }
 
+    let mut params = ChainParams::default();
+
+    // Used to record the balance changes in the native token.
+    params.native_token = Some(wait_native_token(&client).await);
+
+    // Used to show amounts with the alias and the denomination of their token.
+    save_tokens(&db, config, params.native_token.as_ref(), &client).await?;
+
+    // Used to know when unbonds can be withdrawn.
+    match get_pos_params(&client).await {
+        Ok(pos_params) => {
+            params.withdrawable_epoch_offset = Some(pos_params.owned.withdrawable_epoch_offset())
+        }
+        Err(e) => warn!(
+            "Failed to get the proof of stake parameters. (REASON : {})",
+            e
+        ),
+    }
+
+    // Used to record the deposits of the proposals.
+    match get_min_proposal_fund(&client).await {
+        Ok(fund) => params.min_proposal_fund = Some(fund.raw_amount().to_string()),
+        Err(e) => warn!("Failed to get the governance parameters. (REASON : {})", e),
+    }
+
+    // The genesis balances are what is left of the balances once the first
+    // block is committed when the changes of the block are taken out, only
+    // the native token and the configured tokens are read.
+    if current_height == 1 {
+        let mut tokens: Vec<Address> = params.native_token.iter().cloned().collect();
+
+        for token in &config.tokens {
+            if let Ok(address) = Address::decode(&token.address) {
+                if !tokens.contains(&address) {
+                    tokens.push(address);
+                }
+            }
+        }
+
+        let balances = get_balances(&tokens, current_height, &client)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to get the genesis balances. (REASON : {})", e);
+                e
+            })?;
+
+        params.initial_balances = Some(balances);
+    }
+
     /********************
      *
      *  Start indexing
@@ -221,13 +721,21 @@ pub async fn start_indexing(
 
     // Spaw block producer task, this could speed up saving blocks
     // because it does not need to wait for database to finish saving a block.
-    let (mut rx, producer_handler) =
-        spawn_block_producer(current_height as _, chain_name, client, producer_shutdown);
+    let (mut rx, producer_handler) = spawn_block_producer(
+        current_height as _,
+        chain_name,
+        params,
+        client,
+        producer_shutdown,
+    );
+
+    // Keep the hourly and daily statistics up to date in the background.
+    spawn_stats_updater(db.clone(), shutdown.clone());
 
     // Block consumer that stores block into the database
     while let Some(block) = rx.recv().await {
         // block is now the block info and the block results
-        if let Err(e) = db.save_block(&block.0, &block.1).await {
+        if let Err(e) = db.save_block_with_state(&block.0, &block.1, &block.2).await {
             // shutdown producer task
             shutdown.store(true, Ordering::Relaxed);
             tracing::error!("Closing block producer task due to an error saving last block: {e}");
@@ -268,9 +776,27 @@ pub async fn start_indexing(
     Ok(())
 }
 
+// Spawn the task rolling the indexed blocks up into the statistics
+// tables, errors are logged and the update is tried again later.
+fn spawn_stats_updater(db: Database, shutdown: Arc<AtomicBool>) {
+    tokio::spawn(async move {
+        while !shutdown.load(Ordering::Relaxed) {
+            match db.update_stats().await {
+                // catch up without waiting
+                Ok(true) => continue,
+                Ok(false) => {}
+                Err(e) => warn!("Failed to update the statistics. (REASON : {})", e),
+            }
+
+            tokio::time::sleep(Duration::from_secs(STATS_UPDATE_INTERVAL)).await;
+        }
+    });
+}
+
 fn spawn_block_producer(
     current_height: u64,
     chain_name: &str,
+    params: ChainParams,
     client: HttpClient,
     producer_shutdown: Arc<AtomicBool>,
 ) -> (Receiver<BlockInfo>, JoinHandle<Result<(), Error>>) {
@@ -281,7 +807,7 @@ fn spawn_block_producer(
     // Spawn the task
     let chain_name = chain_name.to_string();
     let handler = tokio::spawn(async move {
-        let stream = blocks_stream(current_height as _, chain_name.as_str(), &client);
+        let stream = blocks_stream(current_height as _, chain_name.as_str(), &params, &client);
         pin_mut!(stream);
 
         while let Some(block) = stream.next().await {
@@ -290,7 +816,7 @@ fn spawn_block_producer(
                 break;
             }
 
-            tx.send(block).await?;
+            tx.send(block?).await?;
         }
 
         Ok::<(), Error>(())
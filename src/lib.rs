@@ -1,18 +1,33 @@
+mod accounts;
+mod balances;
+mod bridge_pool;
 mod config;
 pub mod database;
+pub mod decoder;
 mod error;
+mod fees;
+mod governance;
 mod indexer;
 pub(crate) mod queries;
+mod rewards;
 pub mod server;
+mod slashes;
+mod staking;
+mod stewards;
 pub mod tables;
 mod telemetry;
+#[cfg(test)]
+mod test_utils;
 pub mod utils;
+mod validators;
 mod views;
 
 pub use crate::config::{
-    IndexerConfig, JaegerConfig, LogFormat, PrometheusConfig, ServerConfig, Settings,
+    CliSettings, DecodePolicy, IndexerConfig, JaegerConfig, LogFormat, PrometheusConfig,
+    RawTxCompression, ServerConfig, Settings, TokenConfig,
 };
-pub use database::Database;
+pub use database::{BlockState, Database, SaveOptions};
+pub use decoder::{register_decoder, DecodedTx, SideRow, TxContext, TxData, TxDecoder};
 pub use error::Error;
 pub use indexer::start_indexing;
 pub use server::{create_server, start_server, BlockInfo};
@@ -31,6 +46,7 @@ const DB_SAVE_EVDS_DURATION: &str = "db_save_duration_evidences";
 const DB_SAVE_EVDS_BATCH_SIZE: &str = "db_save_batch_size_evidences";
 const DB_SAVE_COMMIT_SIG_DURATION: &str = "db_save_duration_commit_sig";
 const DB_SAVE_COMMIT_SIG_BATCH_SIZE: &str = "db_save_batch_size_commit_sig";
+const DB_DECODE_FAILURES_COUNTER: &str = "db_decode_failures_count";
 const INDEXER_LAST_SAVE_BLOCK_HEIGHT: &str = "indexer_last_save_block_height";
 const INDEXER_LAST_GET_BLOCK_HEIGHT: &str = "indexer_last_get_block_height";
 
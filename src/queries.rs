@@ -23,11 +23,12 @@ pub(crate) fn insert_block_query(network: &str) -> String {
         commit_round, 
         commit_block_id_hash, 
         commit_block_id_parts_header_total, 
-        commit_block_id_parts_header_hash
+        commit_block_id_parts_header_hash,
+        epoch
     ) VALUES (
         $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 
         $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, 
-        $21, $22, $23
+        $21, $22, $23, $24
     )"#,
         network
     )
@@ -0,0 +1,149 @@
+use tendermint_rpc::endpoint::block_results;
+
+use crate::{database::IndexedTx, decoder::TxData, utils};
+
+/// The attribute of the event of an applied `tx_claim_rewards` holding the
+/// claimed amount, in the native token smallest unit.
+pub(crate) const CLAIMED_REWARDS_ATTRIBUTE: &str = "claimed_rewards";
+
+/// The rewards claimed by an applied `tx_claim_rewards`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RewardClaim {
+    /// The claiming address, the validator itself when it claims its own
+    /// rewards.
+    pub delegator: String,
+    pub validator: String,
+    /// The claimed amount, `None` if the event of the transaction does not
+    /// tell it.
+    pub amount: Option<String>,
+    pub tx_hash: Vec<u8>,
+}
+
+/// Returns the reward claims of the transactions of a block. The claimed
+/// amount is not part of the transaction, it is read from the event of the
+/// transaction in `block_results`.
+pub(crate) fn reward_claims(
+    txs: &[IndexedTx],
+    block_results: &block_results::Response,
+) -> Vec<RewardClaim> {
+    let mut claims = vec![];
+
+    for tx in txs {
+        let IndexedTx::Applied { hash, data, .. } = tx else {
+            continue;
+        };
+
+        let TxData::ClaimRewards(claim) = data.as_ref() else {
+            continue;
+        };
+
+        claims.push(RewardClaim {
+            delegator: claim
+                .source
+                .as_ref()
+                .unwrap_or(&claim.validator)
+                .to_string(),
+            validator: claim.validator.to_string(),
+            amount: claimed_amount(block_results, hash),
+            tx_hash: hash.clone(),
+        });
+    }
+
+    claims
+}
+
+/// Returns the amount claimed by the reward claim whose hash is `hash`, as
+/// found in its event.
+pub(crate) fn claimed_amount(
+    block_results: &block_results::Response,
+    hash: &[u8],
+) -> Option<String> {
+    let event = utils::tx_event(block_results, &hex::encode(hash))?;
+
+    event
+        .attributes
+        .iter()
+        .find(|attr| attr.key == CLAIMED_REWARDS_ATTRIBUTE)
+        .and_then(|attr| attr.value.parse::<u128>().ok())
+        .map(|amount| amount.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use namada_sdk::tx::data::pos::Withdraw;
+    use tendermint::abci::Event;
+
+    use super::*;
+    use crate::test_utils::{
+        address, block_results_with_events, ADDRESS as VALIDATOR, OTHER_ADDRESS as DELEGATOR,
+    };
+
+    fn claim(source: Option<&str>) -> Withdraw {
+        Withdraw {
+            validator: address(VALIDATOR),
+            source: source.map(address),
+        }
+    }
+
+    fn claimed(hash: &str, amount: &str) -> Event {
+        Event::new(
+            "applied",
+            [
+                ("hash".to_string(), hash.to_string()),
+                (CLAIMED_REWARDS_ATTRIBUTE.to_string(), amount.to_string()),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_reward_claims() {
+        let txs = vec![
+            IndexedTx::applied("tx_claim_rewards", &[0x0a], claim(Some(DELEGATOR))),
+            // a validator claiming its own rewards
+            IndexedTx::applied("tx_claim_rewards", &[0x0b], claim(None)),
+            IndexedTx::applied("tx_withdraw", &[0x0c], claim(Some(DELEGATOR))),
+            // without event the amount is unknown
+            IndexedTx::applied("tx_claim_rewards", &[0x0d], claim(Some(DELEGATOR))),
+        ];
+        let block_results =
+            block_results_with_events(vec![claimed("0a", "30"), claimed("0b", "12")]);
+
+        assert_eq!(
+            reward_claims(&txs, &block_results),
+            vec![
+                RewardClaim {
+                    delegator: DELEGATOR.to_string(),
+                    validator: VALIDATOR.to_string(),
+                    amount: Some("30".to_string()),
+                    tx_hash: vec![0x0a],
+                },
+                RewardClaim {
+                    delegator: VALIDATOR.to_string(),
+                    validator: VALIDATOR.to_string(),
+                    amount: Some("12".to_string()),
+                    tx_hash: vec![0x0b],
+                },
+                RewardClaim {
+                    delegator: DELEGATOR.to_string(),
+                    validator: VALIDATOR.to_string(),
+                    amount: None,
+                    tx_hash: vec![0x0d],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_claimed_amount() {
+        let block_results =
+            block_results_with_events(vec![claimed("0A", "30"), claimed("0b", "-5")]);
+
+        assert_eq!(
+            claimed_amount(&block_results, &[0x0a]),
+            Some("30".to_string())
+        );
+        // not an amount
+        assert_eq!(claimed_amount(&block_results, &[0x0b]), None);
+        assert_eq!(claimed_amount(&block_results, &[0x0c]), None);
+    }
+}
@@ -1,4 +1,7 @@
+use crate::error::Error;
 use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
 
 /// The relevant information regarding accounts.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
@@ -19,4 +22,73 @@ pub struct AccountUpdates {
     /// Similar to code hash, the last element
     /// is contains the set of public keys this account is associated with.
     pub public_keys: Vec<Vec<String>>,
+
+    /// Every change of the account, oldest first.
+    pub history: Vec<AccountUpdate>,
+}
+
+/// A change of an account, values that were not changed are null.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct AccountUpdate {
+    /// Either `init`, `update` or `reveal_pk`.
+    pub kind: String,
+    pub vp_code_hash: Option<String>,
+    pub threshold: Option<i32>,
+    pub public_keys: Option<Vec<String>>,
+    pub height: i32,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+}
+
+impl TryFrom<&Row> for AccountUpdate {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let kind = row.try_get("kind")?;
+        let vp_code_hash = row
+            .try_get::<Option<Vec<u8>>, _>("vp_code_hash")?
+            .map(hex::encode);
+        let threshold = row.try_get("threshold")?;
+        let public_keys = row.try_get("public_keys")?;
+        let height = row.try_get("height")?;
+        let tx_hash = row.try_get("tx_hash")?;
+
+        Ok(Self {
+            kind,
+            vp_code_hash,
+            threshold,
+            public_keys,
+            height,
+            tx_hash,
+        })
+    }
+}
+
+/// A revealed public key and the implicit address derived from it.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RevealedPublicKey {
+    pub public_key: String,
+    pub address: String,
+    /// Height at which the key was revealed.
+    pub height: i32,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+}
+
+impl TryFrom<&Row> for RevealedPublicKey {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let public_key = row.try_get("public_key")?;
+        let address = row.try_get("address")?;
+        let height = row.try_get("height")?;
+        let tx_hash = row.try_get("tx_hash")?;
+
+        Ok(Self {
+            public_key,
+            address,
+            height,
+            tx_hash,
+        })
+    }
 }
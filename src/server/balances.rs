@@ -0,0 +1,84 @@
+use crate::error::Error;
+use crate::server::tokens::denominate;
+use crate::server::utils::serialize_optional_hex;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// The balance of an address for a token.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct TokenBalance {
+    pub token: String,
+    pub token_alias: Option<String>,
+    /// Amount in the token smallest unit.
+    pub balance: String,
+    /// Exact decimal amount, null if the denomination of the token is unknown.
+    pub balance_denominated: Option<String>,
+}
+
+impl TryFrom<&Row> for TokenBalance {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let token = row.try_get("token")?;
+        let token_alias = row.try_get("token_alias")?;
+        let denomination = row.try_get("denomination")?;
+        let balance: String = row.try_get("balance")?;
+        let balance_denominated = denominate(&balance, denomination);
+
+        Ok(Self {
+            token,
+            token_alias,
+            balance,
+            balance_denominated,
+        })
+    }
+}
+
+/// A change of the balance of an address.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct BalanceChange {
+    pub token: String,
+    pub token_alias: Option<String>,
+    /// Signed amount in the token smallest unit.
+    pub delta: String,
+    pub delta_denominated: Option<String>,
+    /// The balance once the change has been applied.
+    pub balance: String,
+    pub balance_denominated: Option<String>,
+    pub height: i32,
+    /// The transaction causing the change, empty for the genesis balances
+    /// and the changes made by the ledger itself.
+    #[serde(serialize_with = "serialize_optional_hex")]
+    pub tx_hash: Option<Vec<u8>>,
+    /// What caused the change, e.g. `transfer`, `fee`, `bond`, `withdraw`,
+    /// `claim_rewards`, `proposal_deposit`, `ibc` or `genesis`.
+    pub kind: String,
+}
+
+impl TryFrom<&Row> for BalanceChange {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let token = row.try_get("token")?;
+        let token_alias = row.try_get("token_alias")?;
+        let denomination = row.try_get("denomination")?;
+        let delta: String = row.try_get("delta")?;
+        let balance: String = row.try_get("balance")?;
+        let height = row.try_get("height")?;
+        let tx_hash = row.try_get("tx_hash")?;
+        let kind = row.try_get("kind")?;
+
+        Ok(Self {
+            token,
+            token_alias,
+            delta_denominated: denominate(&delta, denomination),
+            delta,
+            balance_denominated: denominate(&balance, denomination),
+            balance,
+            height,
+            tx_hash,
+            kind,
+        })
+    }
+}
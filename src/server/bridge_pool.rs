@@ -0,0 +1,84 @@
+use crate::error::Error;
+use crate::server::tokens::denominate;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// A transfer to Ethereum added to the bridge pool.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct BridgePoolTransfer {
+    /// Keccak hash of the transfer, as used by the Ethereum bridge.
+    #[serde(with = "hex::serde")]
+    pub transfer_hash: Vec<u8>,
+    /// Either `erc20` or `nut`.
+    pub kind: String,
+    /// Address of the Ethereum asset.
+    pub asset: String,
+    /// Address of the Namada token wrapping the asset, unknown for the
+    /// transfers indexed before it was tracked.
+    pub token: Option<String>,
+    /// Ethereum address receiving the asset.
+    pub recipient: String,
+    pub sender: String,
+    /// Amount in the asset smallest unit.
+    pub amount: String,
+    pub amount_denominated: Option<String>,
+    /// Amount in the gas fee token smallest unit.
+    pub gas_fee: String,
+    pub gas_fee_denominated: Option<String>,
+    pub gas_fee_token: String,
+    pub gas_fee_token_alias: Option<String>,
+    pub gas_fee_payer: String,
+    /// One of `pending`, `relayed` or `expired`.
+    pub status: String,
+    pub height: i32,
+    /// Height the transfer was relayed or expired.
+    pub status_height: Option<i32>,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+}
+
+impl TryFrom<&Row> for BridgePoolTransfer {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let transfer_hash = row.try_get("transfer_hash")?;
+        let kind = row.try_get("kind")?;
+        let asset = row.try_get("asset")?;
+        let token = row.try_get("token")?;
+        let recipient = row.try_get("recipient")?;
+        let sender = row.try_get("sender")?;
+        let amount: String = row.try_get("amount")?;
+        let amount_denominated = denominate(&amount, row.try_get("denomination")?);
+        let gas_fee: String = row.try_get("gas_fee")?;
+        let denomination = row.try_get("gas_fee_denomination")?;
+        let gas_fee_denominated = denominate(&gas_fee, denomination);
+        let gas_fee_token = row.try_get("gas_fee_token")?;
+        let gas_fee_token_alias = row.try_get("gas_fee_token_alias")?;
+        let gas_fee_payer = row.try_get("gas_fee_payer")?;
+        let status = row.try_get("status")?;
+        let height = row.try_get("height")?;
+        let status_height = row.try_get("status_height")?;
+        let tx_hash = row.try_get("tx_hash")?;
+
+        Ok(Self {
+            transfer_hash,
+            kind,
+            asset,
+            token,
+            recipient,
+            sender,
+            amount,
+            amount_denominated,
+            gas_fee,
+            gas_fee_denominated,
+            gas_fee_token,
+            gas_fee_token_alias,
+            gas_fee_payer,
+            status,
+            height,
+            status_height,
+            tx_hash,
+        })
+    }
+}
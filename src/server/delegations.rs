@@ -0,0 +1,150 @@
+use crate::error::Error;
+use crate::server::tokens::denominate;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// Tokens bonded by a delegator to a validator, amounts are in the native
+/// token smallest unit, along with their exact decimal value if the
+/// denomination of the native token is known.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Delegation {
+    pub validator: String,
+    pub amount: String,
+    pub amount_denominated: Option<String>,
+    /// Height of the last change of the bonded amount.
+    pub height: i32,
+}
+
+impl TryFrom<&Row> for Delegation {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let validator = row.try_get("validator")?;
+        let denomination = row.try_get("denomination")?;
+        let amount: String = row.try_get("amount")?;
+        let amount_denominated = denominate(&amount, denomination);
+        let height = row.try_get("height")?;
+
+        Ok(Self {
+            validator,
+            amount,
+            amount_denominated,
+            height,
+        })
+    }
+}
+
+/// Tokens unbonded from a validator that have not been withdrawn yet.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct PendingUnbond {
+    pub validator: String,
+    pub amount: String,
+    pub amount_denominated: Option<String>,
+    /// Epoch at which the tokens were unbonded.
+    pub epoch: Option<i32>,
+    /// Epoch from which the tokens can be withdrawn.
+    pub withdrawable_epoch: Option<i32>,
+    pub height: i32,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+}
+
+impl TryFrom<&Row> for PendingUnbond {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let validator = row.try_get("validator")?;
+        let denomination = row.try_get("denomination")?;
+        let amount: String = row.try_get("amount")?;
+        let amount_denominated = denominate(&amount, denomination);
+        let epoch = row.try_get("epoch")?;
+        let withdrawable_epoch = row.try_get("withdrawable_epoch")?;
+        let height = row.try_get("height")?;
+        let tx_hash = row.try_get("tx_hash")?;
+
+        Ok(Self {
+            validator,
+            amount,
+            amount_denominated,
+            epoch,
+            withdrawable_epoch,
+            height,
+            tx_hash,
+        })
+    }
+}
+
+/// Tokens moved from a validator to another one.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Redelegation {
+    pub src_validator: String,
+    pub dest_validator: String,
+    pub amount: String,
+    pub amount_denominated: Option<String>,
+    pub epoch: Option<i32>,
+    pub height: i32,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+}
+
+impl TryFrom<&Row> for Redelegation {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let src_validator = row.try_get("src_validator")?;
+        let dest_validator = row.try_get("dest_validator")?;
+        let denomination = row.try_get("denomination")?;
+        let amount: String = row.try_get("amount")?;
+        let amount_denominated = denominate(&amount, denomination);
+        let epoch = row.try_get("epoch")?;
+        let height = row.try_get("height")?;
+        let tx_hash = row.try_get("tx_hash")?;
+
+        Ok(Self {
+            src_validator,
+            dest_validator,
+            amount,
+            amount_denominated,
+            epoch,
+            height,
+            tx_hash,
+        })
+    }
+}
+
+/// The staking state of an address.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct AddressDelegations {
+    pub bonds: Vec<Delegation>,
+    pub unbonds: Vec<PendingUnbond>,
+    pub redelegations: Vec<Redelegation>,
+}
+
+/// Tokens bonded to a validator by a delegator.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Delegator {
+    pub delegator: String,
+    pub amount: String,
+    pub amount_denominated: Option<String>,
+    pub height: i32,
+}
+
+impl TryFrom<&Row> for Delegator {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let delegator = row.try_get("delegator")?;
+        let denomination = row.try_get("denomination")?;
+        let amount: String = row.try_get("amount")?;
+        let amount_denominated = denominate(&amount, denomination);
+        let height = row.try_get("height")?;
+
+        Ok(Self {
+            delegator,
+            amount,
+            amount_denominated,
+            height,
+        })
+    }
+}
@@ -1,5 +1,10 @@
 pub mod account;
 pub mod address;
 pub mod block;
+pub mod proposal;
+pub mod shielded;
+pub mod stats;
+pub mod steward;
+pub mod token;
 pub mod transaction;
 pub mod validator;
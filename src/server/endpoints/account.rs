@@ -4,7 +4,10 @@ use axum::{
 };
 
 use crate::{
-    server::{account::AccountUpdates, ServerState},
+    server::{
+        account::{AccountUpdate, AccountUpdates, RevealedPublicKey},
+        ServerState,
+    },
     Error,
 };
 use sqlx::Row as TRow;
@@ -13,7 +16,8 @@ use sqlx::Row as TRow;
 ///
 /// This function handles a web request that queries the update history of a specified account.
 /// It returns the updates in JSON format, with each field representing a different aspect
-/// of the account that has been updated. The updates are returned in an ordered manner for each field.
+/// of the account that has been updated. The updates are returned in an ordered manner for each field,
+/// along with the history of the account changes and the height and transaction they happened in.
 ///
 /// # Arguments
 ///
@@ -77,10 +81,29 @@ pub async fn get_account_updates(
         .filter_map(|r| r.try_get::<Vec<String>, _>("public_keys_batch").ok())
         .collect();
 
+    let rows = state.db.account_history(&account_id).await?;
+    let mut history = Vec::with_capacity(rows.len());
+    for row in rows.iter() {
+        history.push(AccountUpdate::try_from(row)?);
+    }
+
     Ok(Json(Some(AccountUpdates {
         account_id,
         thresholds,
         code_hashes,
         public_keys,
+        history,
     })))
 }
+
+// Return the reveal of a public key, or null if it has not been revealed yet.
+pub async fn get_revealed_public_key(
+    State(state): State<ServerState>,
+    Path(public_key): Path<String>,
+) -> Result<Json<Option<RevealedPublicKey>>, Error> {
+    let row = state.db.get_revealed_public_key(&public_key).await?;
+
+    Ok(Json(
+        row.as_ref().map(RevealedPublicKey::try_from).transpose()?,
+    ))
+}
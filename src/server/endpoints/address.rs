@@ -1,14 +1,46 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
+use serde::Deserialize;
+use std::collections::HashMap;
 use tracing::info;
 
 use crate::{
-    server::{ServerState, TxInfo},
+    server::{
+        account::RevealedPublicKey,
+        balances::{BalanceChange, TokenBalance},
+        bridge_pool::BridgePoolTransfer,
+        delegations::{AddressDelegations, Delegation, PendingUnbond, Redelegation},
+        rewards::ClaimedRewards,
+        ServerState, TxInfo,
+    },
     Error,
 };
 
+#[derive(Debug, Deserialize)]
+pub struct BalanceChangesParams {
+    pub token: Option<String>,
+    pub num: Option<i32>,
+    pub offset: Option<i32>,
+}
+
+/// Either the transfers still in the bridge pool or the ones that
+/// have been relayed or have expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BridgePoolStatus {
+    Pending,
+    Completed,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BridgePoolParams {
+    pub status: Option<BridgePoolStatus>,
+    pub num: Option<i32>,
+    pub offset: Option<i32>,
+}
+
 pub async fn get_txs_by_address(
     State(state): State<ServerState>,
     Path(address): Path<String>,
@@ -29,3 +61,134 @@ pub async fn get_txs_by_address(
 
     Ok(Json(Some(response)))
 }
+
+// Return the public key revealed for an implicit address, or null
+// if none has been revealed.
+pub async fn get_address_public_key(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+) -> Result<Json<Option<RevealedPublicKey>>, Error> {
+    info!("calling /address/:address/public_key {}", address);
+
+    let row = state.db.get_address_public_key(&address).await?;
+
+    Ok(Json(
+        row.as_ref().map(RevealedPublicKey::try_from).transpose()?,
+    ))
+}
+
+// Return the balance of the address for every token, as of the
+// block height given by `height` or the last indexed block.
+pub async fn get_address_balances(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<Json<Vec<TokenBalance>>, Error> {
+    info!("calling /address/:address/balances {}", address);
+
+    let rows = state
+        .db
+        .get_balances(&address, params.get("height"))
+        .await?;
+
+    let mut balances: Vec<TokenBalance> = vec![];
+    for row in rows.iter() {
+        balances.push(TokenBalance::try_from(row)?);
+    }
+
+    Ok(Json(balances))
+}
+
+// Return the balance changes of the address, most recent first.
+pub async fn get_address_balance_changes(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+    Query(params): Query<BalanceChangesParams>,
+) -> Result<Json<Vec<BalanceChange>>, Error> {
+    info!("calling /address/:address/balance_changes {}", address);
+
+    let rows = state
+        .db
+        .get_balance_changes(
+            &address,
+            params.token.as_deref(),
+            params.num.as_ref(),
+            params.offset.as_ref(),
+        )
+        .await?;
+
+    let mut changes: Vec<BalanceChange> = vec![];
+    for row in rows.iter() {
+        changes.push(BalanceChange::try_from(row)?);
+    }
+
+    Ok(Json(changes))
+}
+
+// Return the tokens the address has bonded per validator, its pending
+// unbonds and its redelegations.
+pub async fn get_address_delegations(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+) -> Result<Json<AddressDelegations>, Error> {
+    info!("calling /address/:address/delegations {}", address);
+
+    let mut delegations = AddressDelegations::default();
+
+    for row in state.db.get_delegations(&address).await?.iter() {
+        delegations.bonds.push(Delegation::try_from(row)?);
+    }
+
+    for row in state.db.get_pending_unbonds(&address).await?.iter() {
+        delegations.unbonds.push(PendingUnbond::try_from(row)?);
+    }
+
+    for row in state.db.get_redelegations(&address).await?.iter() {
+        delegations.redelegations.push(Redelegation::try_from(row)?);
+    }
+
+    Ok(Json(delegations))
+}
+
+// Return the transfers to Ethereum sent or received by the address, most
+// recent first.
+pub async fn get_address_bridge_pool(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+    Query(params): Query<BridgePoolParams>,
+) -> Result<Json<Vec<BridgePoolTransfer>>, Error> {
+    info!("calling /address/:address/bridge_pool {}", address);
+
+    let completed = params
+        .status
+        .map(|status| status == BridgePoolStatus::Completed);
+
+    let rows = state
+        .db
+        .get_bridge_pool_transfers(
+            &address,
+            completed,
+            params.num.as_ref(),
+            params.offset.as_ref(),
+        )
+        .await?;
+
+    let mut transfers: Vec<BridgePoolTransfer> = vec![];
+    for row in rows.iter() {
+        transfers.push(BridgePoolTransfer::try_from(row)?);
+    }
+
+    Ok(Json(transfers))
+}
+
+// Return the rewards claimed by the address, in total and per epoch.
+pub async fn get_address_rewards(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+) -> Result<Json<ClaimedRewards>, Error> {
+    info!("calling /address/:address/rewards {}", address);
+
+    let rows = state.db.get_claimed_rewards(&address, false).await?;
+
+    Ok(Json(ClaimedRewards::try_from(&rows)?))
+}
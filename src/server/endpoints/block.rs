@@ -6,7 +6,10 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::info;
 
-use crate::{server::ServerState, BlockInfo, Error};
+use crate::{
+    server::{epochs::EpochInfo, ServerState},
+    BlockInfo, Error,
+};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 #[serde(untagged)]
@@ -81,3 +84,27 @@ pub async fn get_last_block(
         Ok(Json(LatestBlock::LastBlock(Box::new(block))))
     }
 }
+
+// Return the height range and time of the blocks of an epoch, or null
+// if none of its blocks has been indexed.
+pub async fn get_epoch(
+    State(state): State<ServerState>,
+    Path(epoch): Path<i32>,
+) -> Result<Json<Option<EpochInfo>>, Error> {
+    info!("calling /epoch/:epoch {}", epoch);
+
+    let row = state.db.get_epoch(Some(epoch)).await?;
+
+    Ok(Json(row.as_ref().map(EpochInfo::try_from).transpose()?))
+}
+
+// Return the epoch of the last indexed block, up to that block.
+pub async fn get_current_epoch(
+    State(state): State<ServerState>,
+) -> Result<Json<Option<EpochInfo>>, Error> {
+    info!("calling /epoch/current");
+
+    let row = state.db.get_epoch(None).await?;
+
+    Ok(Json(row.as_ref().map(EpochInfo::try_from).transpose()?))
+}
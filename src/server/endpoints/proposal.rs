@@ -0,0 +1,45 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::{
+    server::{proposals::Proposal, ServerState},
+    Error,
+};
+
+// Return the governance proposals, the most recent first.
+pub async fn get_proposals(
+    State(state): State<ServerState>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<Json<Vec<Proposal>>, Error> {
+    info!("calling /proposals");
+
+    let rows = state
+        .db
+        .get_proposals(params.get("num"), params.get("offset"))
+        .await?;
+
+    let mut proposals: Vec<Proposal> = vec![];
+    for row in rows.iter() {
+        proposals.push(Proposal::try_from(row)?);
+    }
+
+    Ok(Json(proposals))
+}
+
+pub async fn get_proposal(
+    State(state): State<ServerState>,
+    Path(proposal_id): Path<i64>,
+) -> Result<Json<Option<Proposal>>, Error> {
+    info!("calling /proposal/:proposal_id");
+
+    let row = state.db.get_proposal(proposal_id).await?;
+    let Some(row) = row else {
+        return Ok(Json(None));
+    };
+
+    Ok(Json(Some(Proposal::try_from(&row)?)))
+}
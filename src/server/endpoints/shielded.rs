@@ -0,0 +1,40 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{
+    server::{shielded::ShieldedPoolPoint, utils::Interval, ServerState},
+    Error,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ShieldedHistoryParams {
+    pub interval: Option<Interval>,
+    pub token: Option<String>,
+}
+
+// Return the amount of every token held by the shielded pool at the end
+// of every interval it changed, a day by default.
+pub async fn get_shielded_history(
+    State(state): State<ServerState>,
+    Query(params): Query<ShieldedHistoryParams>,
+) -> Result<Json<Vec<ShieldedPoolPoint>>, Error> {
+    info!("calling /shielded/history");
+
+    let interval = params.interval.unwrap_or_default();
+
+    let rows = state
+        .db
+        .get_shielded_history(interval.as_str(), params.token.as_deref())
+        .await?;
+
+    let mut history: Vec<ShieldedPoolPoint> = vec![];
+    for row in rows.iter() {
+        history.push(ShieldedPoolPoint::try_from(row)?);
+    }
+
+    Ok(Json(history))
+}
@@ -0,0 +1,85 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{
+    server::{
+        stats::{BlockFees, ChainStats, EpochFees, FeeStats},
+        utils::StatsInterval,
+        ServerState,
+    },
+    Error,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct FeeStatsParams {
+    pub token: Option<String>,
+    pub num: Option<i32>,
+    pub offset: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatsParams {
+    pub interval: Option<StatsInterval>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub num: Option<i32>,
+}
+
+// Return the statistics of the last hours or days, a day by default,
+// oldest first.
+pub async fn get_stats(
+    State(state): State<ServerState>,
+    Query(params): Query<StatsParams>,
+) -> Result<Json<Vec<ChainStats>>, Error> {
+    info!("calling /stats");
+
+    let interval = params.interval.unwrap_or_default();
+
+    let rows = state
+        .db
+        .get_stats(
+            interval.as_str(),
+            params.from.as_deref(),
+            params.to.as_deref(),
+            params.num.as_ref(),
+        )
+        .await?;
+
+    let mut stats: Vec<ChainStats> = vec![];
+    for row in rows.iter() {
+        stats.push(ChainStats::try_from(row)?);
+    }
+
+    Ok(Json(stats))
+}
+
+// Return the fees paid per block and per epoch by fee token, the most
+// recent first.
+pub async fn get_fee_stats(
+    State(state): State<ServerState>,
+    Query(params): Query<FeeStatsParams>,
+) -> Result<Json<FeeStats>, Error> {
+    info!("calling /stats/fees");
+
+    let token = params.token.as_deref();
+    let num = params.num.as_ref();
+    let offset = params.offset.as_ref();
+
+    let rows = state.db.get_block_fees(token, num, offset).await?;
+    let mut blocks: Vec<BlockFees> = vec![];
+    for row in rows.iter() {
+        blocks.push(BlockFees::try_from(row)?);
+    }
+
+    let rows = state.db.get_epoch_fees(token, num, offset).await?;
+    let mut epochs: Vec<EpochFees> = vec![];
+    for row in rows.iter() {
+        epochs.push(EpochFees::try_from(row)?);
+    }
+
+    Ok(Json(FeeStats { blocks, epochs }))
+}
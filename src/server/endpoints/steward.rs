@@ -0,0 +1,65 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::Deserialize;
+use tracing::info;
+
+use crate::{
+    server::{
+        stewards::{Steward, StewardCommission},
+        ServerState,
+    },
+    Error,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct StewardsParams {
+    /// Include the resigned and removed stewards.
+    pub all: Option<bool>,
+}
+
+// Return the active PGF stewards, or all of them with `all`.
+pub async fn get_stewards(
+    State(state): State<ServerState>,
+    Query(params): Query<StewardsParams>,
+) -> Result<Json<Vec<Steward>>, Error> {
+    info!("calling /stewards");
+
+    let rows = state.db.get_stewards(params.all.unwrap_or(false)).await?;
+
+    let mut stewards: Vec<Steward> = vec![];
+    for row in rows.iter() {
+        stewards.push(Steward::try_from(row)?);
+    }
+
+    Ok(Json(stewards))
+}
+
+pub async fn get_steward(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+) -> Result<Json<Option<Steward>>, Error> {
+    info!("calling /steward/:address {}", address);
+
+    let row = state.db.get_steward(&address).await?;
+
+    Ok(Json(row.as_ref().map(Steward::try_from).transpose()?))
+}
+
+// Return the changes of the commission split of the steward, oldest first.
+pub async fn get_steward_commissions(
+    State(state): State<ServerState>,
+    Path(address): Path<String>,
+) -> Result<Json<Vec<StewardCommission>>, Error> {
+    info!("calling /steward/:address/commissions {}", address);
+
+    let rows = state.db.get_steward_commissions(&address).await?;
+
+    let mut commissions: Vec<StewardCommission> = vec![];
+    for row in rows.iter() {
+        commissions.push(StewardCommission::try_from(row)?);
+    }
+
+    Ok(Json(commissions))
+}
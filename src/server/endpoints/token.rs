@@ -0,0 +1,21 @@
+use axum::{extract::State, Json};
+use tracing::info;
+
+use crate::{
+    server::{tokens::Token, ServerState},
+    Error,
+};
+
+// Return the tokens known by the indexer, the native token first.
+pub async fn get_tokens(State(state): State<ServerState>) -> Result<Json<Vec<Token>>, Error> {
+    info!("calling /tokens");
+
+    let rows = state.db.get_tokens().await?;
+
+    let mut tokens: Vec<Token> = vec![];
+    for row in rows.iter() {
+        tokens.push(Token::try_from(row)?);
+    }
+
+    Ok(Json(tokens))
+}
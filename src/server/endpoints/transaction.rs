@@ -1,11 +1,16 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Json,
 };
+use std::collections::HashMap;
 use tracing::info;
 
 use crate::{
-    server::{shielded, ServerState, TxInfo},
+    server::{
+        shielded,
+        tx::{DecodeFailure, TxSection},
+        ServerState, TxInfo,
+    },
     Error,
 };
 
@@ -28,11 +33,56 @@ pub async fn get_tx_by_hash(
     Ok(Json(Some(tx)))
 }
 
-// Return a list of the shielded assets and their total compiled using all the shielded transactions (in, internal and out)
+// Return the sections of a transaction, including who signed which sections.
+pub async fn get_tx_sections(
+    State(state): State<ServerState>,
+    Path(hash): Path<String>,
+) -> Result<Json<Option<Vec<TxSection>>>, Error> {
+    info!("calling /tx/{}/sections", hash);
+
+    let hash = hex::decode(hash)?;
+
+    let rows = state.db.get_tx_sections(&hash).await?;
+
+    if rows.is_empty() {
+        return Ok(Json(None));
+    }
+
+    let mut sections: Vec<TxSection> = vec![];
+    for row in rows {
+        sections.push(TxSection::try_from(row)?);
+    }
+
+    Ok(Json(Some(sections)))
+}
+
+// Return the transactions the indexer could not decode, most recent first.
+pub async fn get_decode_failures(
+    State(state): State<ServerState>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<Json<Vec<DecodeFailure>>, Error> {
+    info!("calling /tx/decode_failures");
+
+    let num = params.get("num");
+    let offset = params.get("offset");
+
+    let rows = state.db.get_decode_failures(num, offset).await?;
+
+    let mut failures: Vec<DecodeFailure> = vec![];
+    for row in rows {
+        failures.push(DecodeFailure::try_from(row)?);
+    }
+
+    Ok(Json(failures))
+}
+
+// Return the amount of every token held by the shielded pool, as of the
+// block height given by `height` or the last indexed block.
 pub async fn get_shielded_tx(
     State(state): State<ServerState>,
+    Query(params): Query<HashMap<String, i32>>,
 ) -> Result<Json<shielded::ShieldedAssetsResponse>, Error> {
-    let rows = state.db.get_shielded_tx().await?;
+    let rows = state.db.get_shielded_pool(params.get("height")).await?;
 
     let shielded_assests_response = shielded::ShieldedAssetsResponse::try_from(&rows)?;
 
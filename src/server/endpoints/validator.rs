@@ -6,29 +6,75 @@ use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgRow as Row;
 use sqlx::Row as TRow;
 use std::collections::HashMap;
+use tendermint::Time;
 use tracing::{info, instrument};
 
-use crate::{server::ServerState, Error};
+use crate::{
+    database::DEFAULT_UPTIME_WINDOW,
+    server::{
+        delegations::Delegator,
+        rewards::ClaimedRewards,
+        validators::{
+            ConsensusKey, MissedStreak, SignatureStatus, Validator, ValidatorChange, ValidatorInfo,
+            ValidatorPower, ValidatorSet,
+        },
+        ServerState,
+    },
+    Error,
+};
 
-// Retrieve the count of commit for a range of blocks from the sql query result.
+// Retrieve the number of heights a validator signed and was part of the
+// validator set from the sql query result.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
-#[repr(transparent)]
-struct CommitCount(pub i64);
+struct SignedCount {
+    signed: i64,
+    total: i64,
+}
 
-impl TryFrom<&Row> for CommitCount {
+impl TryFrom<&Row> for SignedCount {
     type Error = Error;
 
     #[instrument(level = "trace", skip(row))]
     fn try_from(row: &Row) -> Result<Self, Self::Error> {
-        let count: i64 = row.try_get("count")?;
+        let signed: i64 = row.try_get("signed")?;
+        let total: i64 = row.try_get("total")?;
 
-        Ok(CommitCount(count))
+        Ok(SignedCount { signed, total })
     }
 }
 
+/// The part of the blocks signed by a validator among the blocks
+/// it had to sign, `0` if it was not part of the validator set.
 #[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
 pub struct UptimeValue {
     pub uptime: f64,
+    pub signed_blocks: i64,
+    pub total_blocks: i64,
+}
+
+impl From<SignedCount> for UptimeValue {
+    fn from(count: SignedCount) -> Self {
+        let uptime = if count.total == 0 {
+            0.0
+        } else {
+            count.signed as f64 / count.total as f64
+        };
+
+        Self {
+            uptime,
+            signed_blocks: count.signed,
+            total_blocks: count.total,
+        }
+    }
+}
+
+/// The signing history of a validator.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct SigningHistory {
+    pub last_signed_height: Option<i32>,
+    pub uptime: UptimeValue,
+    pub signatures: Vec<SignatureStatus>,
+    pub missed_streaks: Vec<MissedStreak>,
 }
 
 pub async fn get_validator_uptime(
@@ -41,20 +87,394 @@ pub async fn get_validator_uptime(
     let start = params.get("start");
     let end = params.get("end");
 
-    let va = hex::decode(validator_address)?;
-    let row = state.db.validator_uptime(&va, start, end).await?;
-    let cc = CommitCount::try_from(&row)?;
+    let addresses = consensus_addresses(&state, &validator_address).await?;
+    let row = state.db.validator_uptime(&addresses, start, end).await?;
+
+    Ok(Json(UptimeValue::from(SignedCount::try_from(&row)?)))
+}
+
+// Return the number of heights signed and missed by every validator over
+// the last `window` heights (500 by default), the highest uptime first.
+pub async fn get_validators_uptime(
+    State(state): State<ServerState>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<Json<Vec<ValidatorUptime>>, Error> {
+    info!("calling /validators/uptime");
+
+    let window = params
+        .get("window")
+        .copied()
+        .unwrap_or(DEFAULT_UPTIME_WINDOW);
+
+    let rows = state.db.get_validators_uptime(window.max(0)).await?;
+
+    let mut validators: Vec<ValidatorUptime> = vec![];
+    for row in rows.iter() {
+        validators.push(ValidatorUptime::try_from(row)?);
+    }
+
+    Ok(Json(validators))
+}
+
+/// Range of heights or of block times (RFC 3339) the proposer statistics are
+/// computed on, heights take precedence.
+#[derive(Debug, Deserialize)]
+pub struct ProposerStatsParams {
+    pub start: Option<i32>,
+    pub end: Option<i32>,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+}
+
+// Return the number of blocks proposed by every validator and the number of
+// blocks it was expected to propose, over a range of heights or time.
+pub async fn get_proposer_stats(
+    State(state): State<ServerState>,
+    Query(params): Query<ProposerStatsParams>,
+) -> Result<Json<Vec<ProposerStats>>, Error> {
+    info!("calling /validators/proposers");
+
+    let (mut start, mut end) = (params.start, params.end);
+
+    if params.start_time.is_some() || params.end_time.is_some() {
+        let start_time = params
+            .start_time
+            .as_deref()
+            .map(Time::parse_from_rfc3339)
+            .transpose()?
+            .map(|t| t.to_rfc3339());
+        let end_time = params
+            .end_time
+            .as_deref()
+            .map(Time::parse_from_rfc3339)
+            .transpose()?
+            .map(|t| t.to_rfc3339());
+
+        let row = state
+            .db
+            .get_height_range(start_time.as_deref(), end_time.as_deref())
+            .await?;
+
+        // no block in the time range
+        let (Some(start_height), Some(end_height)) =
+            (row.try_get("start_height")?, row.try_get("end_height")?)
+        else {
+            return Ok(Json(vec![]));
+        };
+
+        start = start.or(Some(start_height));
+        end = end.or(Some(end_height));
+    }
+
+    let rows = state.db.get_proposer_stats(start, end).await?;
+
+    let mut stats: Vec<ProposerStats> = vec![];
+    for row in rows.iter() {
+        stats.push(ProposerStats::try_from(row)?);
+    }
+
+    Ok(Json(stats))
+}
+
+// Return the blocks proposed by a validator, the most recent first.
+pub async fn get_validator_proposed_blocks(
+    State(state): State<ServerState>,
+    Path(validator_address): Path<String>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<Json<Vec<ProposedBlock>>, Error> {
+    info!("calling /validator/:validator_address/proposed_blocks");
+
+    let addresses = consensus_addresses(&state, &validator_address).await?;
+    let rows = state
+        .db
+        .get_proposed_blocks(&addresses, params.get("num"), params.get("offset"))
+        .await?;
+
+    let mut blocks: Vec<ProposedBlock> = vec![];
+    for row in rows.iter() {
+        blocks.push(ProposedBlock::try_from(row)?);
+    }
+
+    Ok(Json(blocks))
+}
+
+// Return the slashes of all the validators, the most recent first.
+pub async fn get_slashes(
+    State(state): State<ServerState>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<Json<Vec<Slash>>, Error> {
+    info!("calling /slashes");
+
+    let rows = state
+        .db
+        .get_slashes(None, params.get("num"), params.get("offset"))
+        .await?;
+
+    let mut slashes: Vec<Slash> = vec![];
+    for row in rows.iter() {
+        slashes.push(Slash::try_from(row)?);
+    }
+
+    Ok(Json(slashes))
+}
+
+// Return the slashes of a validator and when it was jailed and
+// unjailed, the most recent first.
+pub async fn get_validator_slashes(
+    State(state): State<ServerState>,
+    Path(validator_address): Path<String>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<Json<ValidatorSlashes>, Error> {
+    info!("calling /validator/:validator_address/slashes");
+
+    let validator_address = namada_address(&state, &validator_address).await?;
+
+    let rows = state
+        .db
+        .get_slashes(
+            Some(&validator_address),
+            params.get("num"),
+            params.get("offset"),
+        )
+        .await?;
+
+    let mut slashes: Vec<Slash> = vec![];
+    for row in rows.iter() {
+        slashes.push(Slash::try_from(row)?);
+    }
+
+    let rows = state.db.get_jailings(&validator_address).await?;
+
+    let mut jailings: Vec<Jailing> = vec![];
+    for row in rows.iter() {
+        jailings.push(Jailing::try_from(row)?);
+    }
+
+    Ok(Json(ValidatorSlashes { slashes, jailings }))
+}
+
+// Return the signing history of a validator over the heights in (start, end],
+// by default its whole history, along with its missed blocks streaks.
+pub async fn get_validator_signatures(
+    State(state): State<ServerState>,
+    Path(validator_address): Path<String>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<Json<SigningHistory>, Error> {
+    info!("calling /validator/:validator_address/signatures");
+
+    let start = params.get("start");
+    let end = params.get("end");
+    let num = params.get("num");
+    let offset = params.get("offset");
+
+    let addresses = consensus_addresses(&state, &validator_address).await?;
+
+    let row = state
+        .db
+        .get_validator_last_signed_height(&addresses)
+        .await?;
+    let last_signed_height = row.try_get("height")?;
+
+    // the uptime is over the whole history by default, not the last 500 heights
+    let row = state
+        .db
+        .validator_uptime(&addresses, Some(start.unwrap_or(&-1)), end)
+        .await?;
+    let count = SignedCount::try_from(&row)?;
+
+    let rows = state
+        .db
+        .get_validator_signatures(&addresses, start, end, num, offset)
+        .await?;
+
+    let mut signatures: Vec<SignatureStatus> = vec![];
+    for row in rows.iter() {
+        signatures.push(SignatureStatus::try_from(row)?);
+    }
+
+    let rows = state
+        .db
+        .get_validator_missed_streaks(&addresses, start, end, num, offset)
+        .await?;
+
+    let mut missed_streaks: Vec<MissedStreak> = vec![];
+    for row in rows.iter() {
+        missed_streaks.push(MissedStreak::try_from(row)?);
+    }
+
+    Ok(Json(SigningHistory {
+        last_signed_height,
+        uptime: UptimeValue::from(count),
+        signatures,
+        missed_streaks,
+    }))
+}
+
+// Return the delegators of a validator, identified by its Namada
+// address, with the largest bonded amounts first.
+pub async fn get_validator_delegators(
+    State(state): State<ServerState>,
+    Path(validator_address): Path<String>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<Json<Vec<Delegator>>, Error> {
+    info!("calling /validator/:validator_address/delegators");
+
+    let validator_address = namada_address(&state, &validator_address).await?;
+    let rows = state
+        .db
+        .get_delegators(&validator_address, params.get("num"), params.get("offset"))
+        .await?;
+
+    let mut delegators: Vec<Delegator> = vec![];
+    for row in rows.iter() {
+        delegators.push(Delegator::try_from(row)?);
+    }
+
+    Ok(Json(delegators))
+}
+
+// Return the rewards claimed from a validator, identified by its Namada
+// address, in total and per epoch.
+pub async fn get_validator_rewards(
+    State(state): State<ServerState>,
+    Path(validator_address): Path<String>,
+) -> Result<Json<ClaimedRewards>, Error> {
+    info!("calling /validator/:validator_address/rewards");
+
+    let validator_address = namada_address(&state, &validator_address).await?;
+    let rows = state
+        .db
+        .get_claimed_rewards(&validator_address, true)
+        .await?;
+
+    Ok(Json(ClaimedRewards::try_from(&rows)?))
+}
+
+// Return the validators ordered by their Namada address.
+pub async fn get_validators(
+    State(state): State<ServerState>,
+    Query(params): Query<HashMap<String, i32>>,
+) -> Result<Json<Vec<Validator>>, Error> {
+    info!("calling /validators");
+
+    let rows = state
+        .db
+        .get_validators(params.get("num"), params.get("offset"))
+        .await?;
 
-    // default range is 500 blocks
-    let mut ranger_size: f64 = 500.0;
+    let mut validators: Vec<Validator> = vec![];
+    for row in rows.iter() {
+        validators.push(Validator::try_from(row)?);
+    }
+
+    Ok(Json(validators))
+}
 
-    if let (Some(s), Some(e)) = (start, end) {
-        ranger_size = (e - s).into();
+pub async fn get_validator(
+    State(state): State<ServerState>,
+    Path(validator_address): Path<String>,
+) -> Result<Json<Option<ValidatorInfo>>, Error> {
+    info!("calling /validator/:validator_address");
+
+    let validator_address = namada_address(&state, &validator_address).await?;
+    let row = state.db.get_validator(&validator_address).await?;
+    let Some(row) = row else {
+        return Ok(Json(None));
+    };
+
+    let rows = state.db.get_validator_changes(&validator_address).await?;
+
+    let mut changes: Vec<ValidatorChange> = vec![];
+    for row in rows.iter() {
+        changes.push(ValidatorChange::try_from(row)?);
+    }
+
+    let rows = state
+        .db
+        .get_validator_consensus_keys(&validator_address)
+        .await?;
+
+    let mut consensus_keys: Vec<ConsensusKey> = vec![];
+    for row in rows.iter() {
+        consensus_keys.push(ConsensusKey::try_from(row)?);
     }
 
-    let uv = UptimeValue {
-        uptime: (cc.0 as f64) / ranger_size,
+    Ok(Json(Some(ValidatorInfo {
+        validator: Validator::try_from(&row)?,
+        changes,
+        consensus_keys,
+    })))
+}
+
+// Return the validator set of the block at `block_height`, or null if
+// no validator set was saved before this height.
+pub async fn get_validator_set(
+    State(state): State<ServerState>,
+    Path(block_height): Path<i32>,
+) -> Result<Json<Option<ValidatorSet>>, Error> {
+    info!("calling /validators/set/:block_height");
+
+    let rows = state.db.get_validator_set(block_height).await?;
+    let Some(first) = rows.first() else {
+        return Ok(Json(None));
+    };
+
+    let since_height = first.try_get("height")?;
+    let validators_hash = first.try_get("validators_hash")?;
+
+    let mut validators: Vec<ValidatorPower> = vec![];
+    for row in rows.iter() {
+        validators.push(ValidatorPower::try_from(row)?);
+    }
+
+    Ok(Json(Some(ValidatorSet {
+        height: block_height,
+        since_height,
+        validators_hash,
+        total_voting_power: validators.iter().map(|v| v.voting_power).sum(),
+        validators,
+    })))
+}
+
+// Validators are identified either by their Namada address or by the hex
+// tendermint address of one of their consensus keys, this returns the Namada
+// address, or `validator_address` itself if it is unknown.
+async fn namada_address(state: &ServerState, validator_address: &str) -> Result<String, Error> {
+    let Ok(consensus_address) = hex::decode(validator_address) else {
+        return Ok(validator_address.to_string());
     };
 
-    Ok(Json(uv))
+    match state
+        .db
+        .get_validator_by_consensus_address(&consensus_address)
+        .await?
+    {
+        Some(row) => Ok(row.try_get("validator")?),
+        None => Ok(validator_address.to_string()),
+    }
+}
+
+// Returns the tendermint addresses of all the consensus keys of a validator,
+// those are the ones found in the blocks and commits.
+async fn consensus_addresses(
+    state: &ServerState,
+    validator_address: &str,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let address = namada_address(state, validator_address).await?;
+    let rows = state.db.get_validator_consensus_keys(&address).await?;
+
+    let mut addresses: Vec<Vec<u8>> = vec![];
+    for row in rows.iter() {
+        addresses.push(row.try_get("consensus_address")?);
+    }
+
+    // the validator is unknown if it did not set any consensus key
+    // since the indexing started, e.g. a genesis validator.
+    if addresses.is_empty() {
+        if let Ok(consensus_address) = hex::decode(validator_address) {
+            addresses.push(consensus_address);
+        }
+    }
+
+    Ok(addresses)
 }
@@ -0,0 +1,41 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// The blocks of an epoch.
+///
+/// For the current epoch the last block is the last indexed one.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct EpochInfo {
+    pub epoch: i32,
+    pub start_height: i32,
+    pub end_height: i32,
+    pub num_blocks: i64,
+    /// Time of the first block.
+    pub start_time: String,
+    /// Time of the last block.
+    pub end_time: String,
+}
+
+impl TryFrom<&Row> for EpochInfo {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let epoch = row.try_get("epoch")?;
+        let start_height = row.try_get("start_height")?;
+        let end_height = row.try_get("end_height")?;
+        let num_blocks = row.try_get("num_blocks")?;
+        let start_time = row.try_get("start_time")?;
+        let end_time = row.try_get("end_time")?;
+
+        Ok(Self {
+            epoch,
+            start_height,
+            end_height,
+            num_blocks,
+            start_time,
+            end_time,
+        })
+    }
+}
@@ -19,17 +19,42 @@ pub mod tx;
 pub use blocks::BlockInfo;
 pub use tx::TxInfo;
 pub mod account;
+pub mod balances;
+pub mod bridge_pool;
+pub mod delegations;
 mod endpoints;
+pub mod epochs;
+pub mod proposals;
+pub mod rewards;
 pub mod shielded;
+pub mod stats;
+pub mod stewards;
+pub mod tokens;
 mod utils;
+pub mod validators;
 pub(crate) use utils::{from_hex, serialize_hex};
 
 use self::endpoints::{
-    account::get_account_updates,
-    address::get_txs_by_address,
-    block::{get_block_by_hash, get_block_by_height, get_last_block},
-    transaction::{get_shielded_tx, get_tx_by_hash, get_vote_proposal},
-    validator::get_validator_uptime,
+    account::{get_account_updates, get_revealed_public_key},
+    address::{
+        get_address_balance_changes, get_address_balances, get_address_bridge_pool,
+        get_address_delegations, get_address_public_key, get_address_rewards, get_txs_by_address,
+    },
+    block::{get_block_by_hash, get_block_by_height, get_current_epoch, get_epoch, get_last_block},
+    proposal::{get_proposal, get_proposals},
+    shielded::get_shielded_history,
+    stats::{get_fee_stats, get_stats},
+    steward::{get_steward, get_steward_commissions, get_stewards},
+    token::get_tokens,
+    transaction::{
+        get_decode_failures, get_shielded_tx, get_tx_by_hash, get_tx_sections, get_vote_proposal,
+    },
+    validator::{
+        get_proposer_stats, get_slashes, get_validator, get_validator_delegators,
+        get_validator_proposed_blocks, get_validator_rewards, get_validator_set,
+        get_validator_signatures, get_validator_slashes, get_validator_uptime, get_validators,
+        get_validators_uptime,
+    },
 };
 
 pub const HTTP_DURATION_SECONDS_BUCKETS: &[f64; 11] = &[
@@ -47,17 +72,78 @@ fn server_routes(state: ServerState) -> Router<()> {
         .allow_origin(Any);
     Router::new()
         .route("/address/:address", get(get_txs_by_address))
+        .route("/address/:address/balances", get(get_address_balances))
+        .route(
+            "/address/:address/balance_changes",
+            get(get_address_balance_changes),
+        )
+        .route(
+            "/address/:address/delegations",
+            get(get_address_delegations),
+        )
+        .route("/address/:address/public_key", get(get_address_public_key))
+        .route("/address/:address/rewards", get(get_address_rewards))
+        .route(
+            "/address/:address/bridge_pool",
+            get(get_address_bridge_pool),
+        )
         .route("/block/height/:block_height", get(get_block_by_height))
         .route("/block/hash/:block_hash", get(get_block_by_hash))
         .route("/block/last", get(get_last_block))
+        .route("/epoch/current", get(get_current_epoch))
+        .route("/epoch/:epoch", get(get_epoch))
         .route("/tx/:tx_hash", get(get_tx_by_hash))
+        .route("/tx/:tx_hash/sections", get(get_tx_sections))
         .route("/tx/vote_proposal/:proposal_id", get(get_vote_proposal))
         .route("/tx/shielded", get(get_shielded_tx))
+        .route("/shielded/history", get(get_shielded_history))
+        .route("/stats", get(get_stats))
+        .route("/stats/fees", get(get_fee_stats))
+        .route("/stewards", get(get_stewards))
+        .route("/steward/:address", get(get_steward))
+        .route(
+            "/steward/:address/commissions",
+            get(get_steward_commissions),
+        )
+        .route("/tokens", get(get_tokens))
+        .route("/tx/decode_failures", get(get_decode_failures))
+        .route("/proposals", get(get_proposals))
+        .route("/proposal/:proposal_id", get(get_proposal))
         .route("/account/updates/:account_id", get(get_account_updates))
+        .route(
+            "/account/public_key/:public_key",
+            get(get_revealed_public_key),
+        )
+        .route("/validators", get(get_validators))
+        .route("/validators/set/:block_height", get(get_validator_set))
+        .route("/validators/uptime", get(get_validators_uptime))
+        .route("/validators/proposers", get(get_proposer_stats))
+        .route("/slashes", get(get_slashes))
+        .route("/validator/:validator_address", get(get_validator))
         .route(
             "/validator/:validator_address/uptime",
             get(get_validator_uptime),
         )
+        .route(
+            "/validator/:validator_address/signatures",
+            get(get_validator_signatures),
+        )
+        .route(
+            "/validator/:validator_address/proposed_blocks",
+            get(get_validator_proposed_blocks),
+        )
+        .route(
+            "/validator/:validator_address/slashes",
+            get(get_validator_slashes),
+        )
+        .route(
+            "/validator/:validator_address/delegators",
+            get(get_validator_delegators),
+        )
+        .route(
+            "/validator/:validator_address/rewards",
+            get(get_validator_rewards),
+        )
         .layer(cors)
         .with_state(state)
 }
@@ -0,0 +1,105 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// Number of voters per vote, whatever their stake, and their voting power.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct VoteTally {
+    pub yay_voters: i64,
+    pub nay_voters: i64,
+    pub abstain_voters: i64,
+    /// The stake of the voters at the voting end epoch, in the native token
+    /// smallest unit, null until the proposal ended.
+    pub yay_power: Option<String>,
+    pub nay_power: Option<String>,
+    pub abstain_power: Option<String>,
+}
+
+impl VoteTally {
+    // `voter` is the prefix of the tally columns, `validator` or `delegator`.
+    fn from_row(row: &Row, voter: &str) -> Result<Self, Error> {
+        let yay_voters = row.try_get(format!("{voter}_yay_voters").as_str())?;
+        let nay_voters = row.try_get(format!("{voter}_nay_voters").as_str())?;
+        let abstain_voters = row.try_get(format!("{voter}_abstain_voters").as_str())?;
+        let yay_power = row.try_get(format!("{voter}_yay_power").as_str())?;
+        let nay_power = row.try_get(format!("{voter}_nay_power").as_str())?;
+        let abstain_power = row.try_get(format!("{voter}_abstain_power").as_str())?;
+
+        Ok(Self {
+            yay_voters,
+            nay_voters,
+            abstain_voters,
+            yay_power,
+            nay_power,
+            abstain_power,
+        })
+    }
+}
+
+/// The votes of validators and delegators, only the last vote of
+/// every voter is counted.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Default)]
+pub struct ProposalTally {
+    pub validators: VoteTally,
+    pub delegators: VoteTally,
+}
+
+/// A governance proposal and its vote tally.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Proposal {
+    pub id: i64,
+    pub author: String,
+    #[serde(with = "hex::serde")]
+    pub content_hash: Vec<u8>,
+    /// The proposal content, null if it was not found in the transaction.
+    pub content: Option<Value>,
+    /// The proposal type, e.g. `Default` or `PGFSteward`.
+    pub kind: String,
+    pub kind_data: Option<Value>,
+    pub voting_start_epoch: i32,
+    pub voting_end_epoch: i32,
+    pub grace_epoch: i32,
+    pub height: i32,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+    pub tally: ProposalTally,
+}
+
+impl TryFrom<&Row> for Proposal {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let id = row.try_get("id")?;
+        let author = row.try_get("author")?;
+        let content_hash = row.try_get("content_hash")?;
+        let content = row.try_get("content")?;
+        let kind = row.try_get("kind")?;
+        let kind_data = row.try_get("kind_data")?;
+        let voting_start_epoch = row.try_get("voting_start_epoch")?;
+        let voting_end_epoch = row.try_get("voting_end_epoch")?;
+        let grace_epoch = row.try_get("grace_epoch")?;
+        let height = row.try_get("height")?;
+        let tx_hash = row.try_get("tx_hash")?;
+        let tally = ProposalTally {
+            validators: VoteTally::from_row(row, "validator")?,
+            delegators: VoteTally::from_row(row, "delegator")?,
+        };
+
+        Ok(Self {
+            id,
+            author,
+            content_hash,
+            content,
+            kind,
+            kind_data,
+            voting_start_epoch,
+            voting_end_epoch,
+            grace_epoch,
+            height,
+            tx_hash,
+            tally,
+        })
+    }
+}
@@ -0,0 +1,68 @@
+use crate::error::Error;
+use crate::server::tokens::denominate;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// The rewards claimed during an epoch.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct EpochRewards {
+    /// Null for the claims of blocks saved without their epoch.
+    pub epoch: Option<i32>,
+    /// Amount in the native token smallest unit.
+    pub amount: String,
+    pub amount_denominated: Option<String>,
+    pub num_claims: i64,
+}
+
+impl TryFrom<&Row> for EpochRewards {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let epoch = row.try_get("epoch")?;
+        let denomination = row.try_get("denomination")?;
+        let amount: String = row.try_get("amount")?;
+        let amount_denominated = denominate(&amount, denomination);
+        let num_claims = row.try_get("num_claims")?;
+
+        Ok(Self {
+            epoch,
+            amount,
+            amount_denominated,
+            num_claims,
+        })
+    }
+}
+
+/// The rewards claimed by a delegator, or from a validator, in total and
+/// per epoch, the most recent first.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ClaimedRewards {
+    /// Amount in the native token smallest unit.
+    pub total: String,
+    pub total_denominated: Option<String>,
+    pub epochs: Vec<EpochRewards>,
+}
+
+impl TryFrom<&Vec<Row>> for ClaimedRewards {
+    type Error = Error;
+
+    fn try_from(rows: &Vec<Row>) -> Result<Self, Self::Error> {
+        let (total, denomination) = match rows.first() {
+            Some(row) => (row.try_get("total")?, row.try_get("denomination")?),
+            None => ("0".to_string(), None),
+        };
+        let total_denominated = denominate(&total, denomination);
+
+        let mut epochs: Vec<EpochRewards> = vec![];
+        for row in rows {
+            epochs.push(EpochRewards::try_from(row)?);
+        }
+
+        Ok(Self {
+            total,
+            total_denominated,
+            epochs,
+        })
+    }
+}
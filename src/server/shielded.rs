@@ -1,5 +1,5 @@
 use crate::error::Error;
-use crate::MASP_ADDR;
+use crate::server::tokens::denominate;
 use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgRow as Row;
 use sqlx::Row as TRow;
@@ -10,6 +10,33 @@ pub struct ShieldedAssetsResponse {
     pub shielded_assets: ShieldedAssets,
 }
 
+/// The amount of a token held by the shielded pool, as exact decimal strings.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ShieldedAsset {
+    pub token_alias: Option<String>,
+    /// Amount in the token smallest unit.
+    pub amount: String,
+    /// Null if the denomination of the token is unknown.
+    pub amount_denominated: Option<String>,
+}
+
+impl TryFrom<&Row> for ShieldedAsset {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let token_alias = row.try_get("token_alias")?;
+        let denomination = row.try_get("denomination")?;
+        let amount: String = row.try_get("amount")?;
+        let amount_denominated = denominate(&amount, denomination);
+
+        Ok(Self {
+            token_alias,
+            amount,
+            amount_denominated,
+        })
+    }
+}
+
 impl TryFrom<&Vec<Row>> for ShieldedAssetsResponse {
     type Error = Error;
 
@@ -18,35 +45,46 @@ impl TryFrom<&Vec<Row>> for ShieldedAssetsResponse {
 
         for row in rows {
             let token: String = row.try_get("token")?;
+            shielded_assets.insert(token, ShieldedAsset::try_from(row)?);
+        }
 
-            let amount_str: &str = row.try_get("amount")?;
-            let amount: f64 = amount_str.parse::<f64>()?;
-
-            let target: String = row.try_get("target")?;
-            let source: String = row.try_get("source")?;
+        Ok(Self { shielded_assets })
+    }
+}
 
-            if target == MASP_ADDR || source == MASP_ADDR {
-                if target == source {
-                    continue;
-                }
+pub type ShieldedAssets = HashMap<String, ShieldedAsset>;
 
-                let mut total = match shielded_assets.get(&token) {
-                    Some(v) => *v,
-                    None => 0.0,
-                };
+/// The amount of a token held by the shielded pool at the end of an
+/// interval, and its change over the interval.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ShieldedPoolPoint {
+    /// Start of the interval, in UTC.
+    pub time: String,
+    pub token: String,
+    #[serde(flatten)]
+    pub asset: ShieldedAsset,
+    /// Signed amount in the token smallest unit.
+    pub change: String,
+    pub change_denominated: Option<String>,
+}
 
-                if target == MASP_ADDR {
-                    total += amount;
-                } else {
-                    total -= amount;
-                }
+impl TryFrom<&Row> for ShieldedPoolPoint {
+    type Error = Error;
 
-                shielded_assets.insert(token, total);
-            };
-        }
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let time = row.try_get("time")?;
+        let token = row.try_get("token")?;
+        let asset = ShieldedAsset::try_from(row)?;
+        let denomination = row.try_get("denomination")?;
+        let change: String = row.try_get("change")?;
+        let change_denominated = denominate(&change, denomination);
 
-        Ok(Self { shielded_assets })
+        Ok(Self {
+            time,
+            token,
+            asset,
+            change,
+            change_denominated,
+        })
     }
 }
-
-pub type ShieldedAssets = HashMap<String, f64>;
@@ -0,0 +1,173 @@
+use crate::error::Error;
+use crate::server::tokens::denominate;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+use std::collections::HashMap;
+
+/// The fees paid and the gas used by the wrapper transactions of a block
+/// paying in the same token.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct BlockFees {
+    pub height: i32,
+    pub epoch: Option<i32>,
+    pub token: String,
+    pub token_alias: Option<String>,
+    pub tx_count: i32,
+    /// Amount in the token smallest unit.
+    pub total_fee: String,
+    pub total_fee_denominated: Option<String>,
+    pub gas_limit: i64,
+    /// Gas used by the decrypted transactions, known once the next block
+    /// is indexed.
+    pub gas_used: i64,
+    /// Amount in the token smallest unit.
+    pub median_fee_per_gas: String,
+    pub median_fee_per_gas_denominated: Option<String>,
+}
+
+impl TryFrom<&Row> for BlockFees {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let height = row.try_get("height")?;
+        let epoch = row.try_get("epoch")?;
+        let token = row.try_get("token")?;
+        let token_alias = row.try_get("token_alias")?;
+        let denomination = row.try_get("denomination")?;
+        let tx_count = row.try_get("tx_count")?;
+        let total_fee: String = row.try_get("total_fee")?;
+        let total_fee_denominated = denominate(&total_fee, denomination);
+        let gas_limit = row.try_get("gas_limit")?;
+        let gas_used = row.try_get("gas_used")?;
+        let median_fee_per_gas: String = row.try_get("median_fee_per_gas")?;
+        let median_fee_per_gas_denominated = denominate(&median_fee_per_gas, denomination);
+
+        Ok(Self {
+            height,
+            epoch,
+            token,
+            token_alias,
+            tx_count,
+            total_fee,
+            total_fee_denominated,
+            gas_limit,
+            gas_used,
+            median_fee_per_gas,
+            median_fee_per_gas_denominated,
+        })
+    }
+}
+
+/// The fees paid and the gas used by the wrapper transactions of an epoch
+/// paying in the same token.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct EpochFees {
+    pub epoch: i32,
+    pub token: String,
+    pub token_alias: Option<String>,
+    pub tx_count: i32,
+    /// Amount in the token smallest unit.
+    pub total_fee: String,
+    pub total_fee_denominated: Option<String>,
+    pub gas_limit: i64,
+    pub gas_used: i64,
+    /// Amount in the token smallest unit.
+    pub median_fee_per_gas: String,
+    pub median_fee_per_gas_denominated: Option<String>,
+    /// First block of the epoch with fees in this token.
+    pub start_height: i32,
+    /// Last block of the epoch with fees in this token.
+    pub end_height: i32,
+}
+
+impl TryFrom<&Row> for EpochFees {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let epoch = row.try_get("epoch")?;
+        let token = row.try_get("token")?;
+        let token_alias = row.try_get("token_alias")?;
+        let denomination = row.try_get("denomination")?;
+        let tx_count = row.try_get("tx_count")?;
+        let total_fee: String = row.try_get("total_fee")?;
+        let total_fee_denominated = denominate(&total_fee, denomination);
+        let gas_limit = row.try_get("gas_limit")?;
+        let gas_used = row.try_get("gas_used")?;
+        let median_fee_per_gas: String = row.try_get("median_fee_per_gas")?;
+        let median_fee_per_gas_denominated = denominate(&median_fee_per_gas, denomination);
+        let start_height = row.try_get("start_height")?;
+        let end_height = row.try_get("end_height")?;
+
+        Ok(Self {
+            epoch,
+            token,
+            token_alias,
+            tx_count,
+            total_fee,
+            total_fee_denominated,
+            gas_limit,
+            gas_used,
+            median_fee_per_gas,
+            median_fee_per_gas_denominated,
+            start_height,
+            end_height,
+        })
+    }
+}
+
+/// The most recent fee rollups per block and per epoch.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct FeeStats {
+    pub blocks: Vec<BlockFees>,
+    pub epochs: Vec<EpochFees>,
+}
+
+/// Statistics of the blocks of an hour or a day.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ChainStats {
+    /// Start of the interval, in UTC.
+    pub time: String,
+    pub start_height: i32,
+    pub end_height: i32,
+    pub num_blocks: i32,
+    /// Number of decrypted transactions.
+    pub num_txs: i32,
+    pub txs_by_code_type: HashMap<String, i64>,
+    /// Share of the decrypted transactions that were applied successfully,
+    /// null without transactions.
+    pub success_rate: Option<f64>,
+    /// Number of distinct fee payers of the wrapper transactions.
+    pub unique_signers: i32,
+    /// Average time between two blocks in seconds, null with a single block.
+    pub avg_block_interval: Option<f64>,
+}
+
+impl TryFrom<&Row> for ChainStats {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let time = row.try_get("time")?;
+        let start_height = row.try_get("start_height")?;
+        let end_height = row.try_get("end_height")?;
+        let num_blocks = row.try_get("num_blocks")?;
+        let num_txs = row.try_get("num_txs")?;
+        let txs_by_code_type: serde_json::Value = row.try_get("txs_by_code_type")?;
+        let txs_by_code_type = serde_json::from_value(txs_by_code_type)?;
+        let success_rate = row.try_get("success_rate")?;
+        let unique_signers = row.try_get("unique_signers")?;
+        let avg_block_interval = row.try_get("avg_block_interval")?;
+
+        Ok(Self {
+            time,
+            start_height,
+            end_height,
+            num_blocks,
+            num_txs,
+            txs_by_code_type,
+            success_rate,
+            unique_signers,
+            avg_block_interval,
+        })
+    }
+}
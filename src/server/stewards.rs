@@ -0,0 +1,75 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// A PGF steward.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Steward {
+    pub address: String,
+    /// One of `active`, `resigned` or `removed`.
+    pub status: String,
+    /// The split of the rewards of the steward, as a map of addresses to
+    /// decimal rates, null if it is unknown.
+    pub commission: Option<Value>,
+    /// Height the steward was first seen.
+    pub height: i32,
+    pub last_change_height: i32,
+}
+
+impl TryFrom<&Row> for Steward {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let address = row.try_get("address")?;
+        let status = row.try_get("status")?;
+        let commission = row.try_get("commission")?;
+        let height = row.try_get("height")?;
+        let last_change_height = row.try_get("last_change_height")?;
+
+        Ok(Self {
+            address,
+            status,
+            commission,
+            height,
+            last_change_height,
+        })
+    }
+}
+
+/// A change of the commission split of a steward.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct StewardCommission {
+    /// Either `add` when the steward is added by a proposal, or
+    /// `update_commission`.
+    pub kind: String,
+    pub commission: Value,
+    /// The proposal adding the steward.
+    pub proposal_id: Option<i64>,
+    pub height: i32,
+    /// The update transaction.
+    pub tx_hash: Option<String>,
+}
+
+impl TryFrom<&Row> for StewardCommission {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let kind = row.try_get("kind")?;
+        let commission = row.try_get("commission")?;
+        let proposal_id = row.try_get("proposal_id")?;
+        let height = row.try_get("height")?;
+        let tx_hash = row
+            .try_get::<Option<Vec<u8>>, _>("tx_hash")?
+            .map(hex::encode);
+
+        Ok(Self {
+            kind,
+            commission,
+            proposal_id,
+            height,
+            tx_hash,
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+
+/// A token known by the indexer.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Token {
+    pub address: String,
+    pub alias: Option<String>,
+    /// Number of decimal places of the token, null if unknown.
+    pub denomination: Option<i32>,
+    pub is_native: bool,
+}
+
+impl TryFrom<&Row> for Token {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let address = row.try_get("address")?;
+        let alias = row.try_get("alias")?;
+        let denomination = row.try_get("denomination")?;
+        let is_native = row.try_get("is_native")?;
+
+        Ok(Self {
+            address,
+            alias,
+            denomination,
+            is_native,
+        })
+    }
+}
+
+/// Returns the raw `amount` of a token with `denomination` decimal places
+/// as an exact decimal string, e.g. `-1500000` with 6 decimal places is `-1.5`.
+///
+/// Returns `None` if the denomination is unknown or `amount` is not an
+/// integer.
+pub(crate) fn denominate(amount: &str, denomination: Option<i32>) -> Option<String> {
+    let denomination = usize::try_from(denomination?).ok()?;
+
+    let (sign, digits) = match amount.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", amount),
+    };
+
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    // at least one digit before the decimal point
+    let digits = format!("{:0>width$}", digits, width = denomination + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - denomination);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        Some(format!("{sign}{integer}"))
+    } else {
+        Some(format!("{sign}{integer}.{fraction}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_denominate() {
+        assert_eq!(denominate("1500000", Some(6)).as_deref(), Some("1.5"));
+        assert_eq!(denominate("-25", Some(6)).as_deref(), Some("-0.000025"));
+        assert_eq!(denominate("42000000", Some(6)).as_deref(), Some("42"));
+        assert_eq!(denominate("0", Some(6)).as_deref(), Some("0"));
+        assert_eq!(denominate("123", Some(0)).as_deref(), Some("123"));
+        assert_eq!(
+            denominate("340282366920938463463374607431768211456", Some(18)).as_deref(),
+            Some("340282366920938463463.374607431768211456")
+        );
+        assert_eq!(denominate("1500000", None), None);
+        assert_eq!(denominate("1.5", Some(6)), None);
+    }
+}
@@ -108,6 +108,91 @@ impl TryFrom<Row> for TxInfo {
     }
 }
 
+/// A section of a transaction, as found in the `tx_sections` table.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct TxSection {
+    /// Position of the section within the transaction.
+    pub index: i32,
+    /// The section kind, e.g. `code`, `data` or `signature`.
+    pub kind: String,
+    /// The hash of the section, this is what signatures refer to.
+    #[serde(with = "hex::serde")]
+    pub hash: Vec<u8>,
+    /// Size in bytes of the borsh encoded section.
+    pub size: i32,
+    /// The account that signed, only for signature sections
+    /// signed on behalf of an address.
+    pub signer_address: Option<String>,
+    /// The public keys that signed, only for signature sections.
+    pub signer_public_keys: Option<Vec<String>>,
+    /// The hashes of the sections covered by the signature.
+    pub signature_targets: Option<Vec<String>>,
+}
+
+impl TryFrom<Row> for TxSection {
+    type Error = Error;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        let index = row.try_get("section_index")?;
+        let kind = row.try_get("kind")?;
+        let hash = row.try_get("hash")?;
+        let size = row.try_get("size")?;
+        let signer_address = row.try_get("signer_address")?;
+        let signer_public_keys = row.try_get("signer_public_keys")?;
+        let signature_targets = row
+            .try_get::<Option<Vec<Vec<u8>>>, _>("signature_targets")?
+            .map(|targets| targets.into_iter().map(hex::encode).collect());
+
+        Ok(Self {
+            index,
+            kind,
+            hash,
+            size,
+            signer_address,
+            signer_public_keys,
+            signature_targets,
+        })
+    }
+}
+
+// A transaction that could not be decoded by the indexer.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct DecodeFailure {
+    /// The hash of the transaction bytes if the transaction envelope itself
+    /// could not be decoded.
+    pub tx_hash: String,
+    #[serde(with = "hex::serde")]
+    pub block_id: Vec<u8>,
+    pub height: i32,
+    pub code_type: Option<String>,
+    pub error: String,
+    /// The data section, or the whole transaction if it has none.
+    #[serde(with = "hex::serde")]
+    pub payload: Vec<u8>,
+}
+
+impl TryFrom<Row> for DecodeFailure {
+    type Error = Error;
+
+    fn try_from(row: Row) -> Result<Self, Self::Error> {
+        let tx_hash = hex::encode(row.try_get::<Vec<u8>, _>("tx_hash")?);
+        let block_id = row.try_get("block_id")?;
+        let height = row.try_get("height")?;
+        let code_type = row.try_get("code_type")?;
+        let error = row.try_get("error")?;
+        let payload = row.try_get("payload")?;
+
+        Ok(Self {
+            tx_hash,
+            block_id,
+            height,
+            code_type,
+            error,
+            payload,
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
 pub struct VoteProposalTx {
     pub id: i64,
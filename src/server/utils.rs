@@ -29,3 +29,45 @@ where
         serializer.serialize_str("")
     }
 }
+
+/// The length of the buckets of a time series.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Interval {
+    Hour,
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl Interval {
+    /// The postgres `DATE_TRUNC` field of the interval.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Interval::Hour => "hour",
+            Interval::Day => "day",
+            Interval::Week => "week",
+            Interval::Month => "month",
+        }
+    }
+}
+
+/// The length of the buckets of the statistics rollups.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsInterval {
+    Hour,
+    #[default]
+    Day,
+}
+
+impl StatsInterval {
+    /// The suffix of the statistics table of the interval.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsInterval::Hour => "hour",
+            StatsInterval::Day => "day",
+        }
+    }
+}
@@ -0,0 +1,425 @@
+use crate::error::Error;
+use crate::server::tokens::denominate;
+use crate::server::utils::serialize_optional_hex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::postgres::PgRow as Row;
+use sqlx::Row as TRow;
+use tendermint_proto::types::BlockIdFlag;
+
+/// The current state of a validator, rates are decimal strings.
+///
+/// Fields that were never changed by a transaction are null for the
+/// validators created at genesis.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Validator {
+    pub address: String,
+    pub consensus_key: Option<String>,
+    pub commission_rate: Option<String>,
+    pub max_commission_rate_change: Option<String>,
+    pub email: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub discord_handle: Option<String>,
+    pub avatar: Option<String>,
+    /// Either `active`, `inactive` or `jailed`.
+    pub state: String,
+    /// Height at which the validator was created, null for genesis validators.
+    pub height: Option<i32>,
+    pub last_change_height: i32,
+}
+
+impl TryFrom<&Row> for Validator {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let address = row.try_get("address")?;
+        let consensus_key = row.try_get("consensus_key")?;
+        let commission_rate = row.try_get("commission_rate")?;
+        let max_commission_rate_change = row.try_get("max_commission_rate_change")?;
+        let email = row.try_get("email")?;
+        let description = row.try_get("description")?;
+        let website = row.try_get("website")?;
+        let discord_handle = row.try_get("discord_handle")?;
+        let avatar = row.try_get("avatar")?;
+        let state = row.try_get("state")?;
+        let height = row.try_get("height")?;
+        let last_change_height = row.try_get("last_change_height")?;
+
+        Ok(Self {
+            address,
+            consensus_key,
+            commission_rate,
+            max_commission_rate_change,
+            email,
+            description,
+            website,
+            discord_handle,
+            avatar,
+            state,
+            height,
+            last_change_height,
+        })
+    }
+}
+
+/// A change of a validator, `kind` is the transaction type without its
+/// `tx_` prefix and `data` the transaction data.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorChange {
+    pub kind: String,
+    pub data: Option<Value>,
+    pub height: i32,
+    #[serde(with = "hex::serde")]
+    pub tx_hash: Vec<u8>,
+}
+
+impl TryFrom<&Row> for ValidatorChange {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let kind = row.try_get("kind")?;
+        let data = row.try_get("data")?;
+        let height = row.try_get("height")?;
+        let tx_hash = row.try_get("tx_hash")?;
+
+        Ok(Self {
+            kind,
+            data,
+            height,
+            tx_hash,
+        })
+    }
+}
+
+/// A consensus key of a validator, `consensus_address` is its tendermint
+/// address used in blocks and commits.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ConsensusKey {
+    #[serde(with = "hex::serde")]
+    pub consensus_address: Vec<u8>,
+    pub consensus_key: String,
+    /// Height of the transaction that set the key, or of the block whose
+    /// validator set it was first read from.
+    pub height: i32,
+    /// The transaction that set the key, empty for the keys read from a
+    /// validator set.
+    #[serde(serialize_with = "serialize_optional_hex")]
+    pub tx_hash: Option<Vec<u8>>,
+}
+
+impl TryFrom<&Row> for ConsensusKey {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let consensus_address = row.try_get("consensus_address")?;
+        let consensus_key = row.try_get("consensus_key")?;
+        let height = row.try_get("height")?;
+        let tx_hash = row.try_get("tx_hash")?;
+
+        Ok(Self {
+            consensus_address,
+            consensus_key,
+            height,
+            tx_hash,
+        })
+    }
+}
+
+/// A validator, the history of its changes and its consensus keys.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorInfo {
+    #[serde(flatten)]
+    pub validator: Validator,
+    pub changes: Vec<ValidatorChange>,
+    pub consensus_keys: Vec<ConsensusKey>,
+}
+
+/// A validator of a validator set and its voting power.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorPower {
+    #[serde(with = "hex::serde")]
+    pub consensus_address: Vec<u8>,
+    /// The Namada address, null if the consensus key is unknown.
+    pub address: Option<String>,
+    pub voting_power: i64,
+}
+
+impl TryFrom<&Row> for ValidatorPower {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let consensus_address = row.try_get("consensus_address")?;
+        let address = row.try_get("validator")?;
+        let voting_power = row.try_get("voting_power")?;
+
+        Ok(Self {
+            consensus_address,
+            address,
+            voting_power,
+        })
+    }
+}
+
+/// The validators of a block, `since_height` is the height from which
+/// the set is the same.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorSet {
+    pub height: i32,
+    pub since_height: i32,
+    #[serde(with = "hex::serde")]
+    pub validators_hash: Vec<u8>,
+    pub total_voting_power: i64,
+    pub validators: Vec<ValidatorPower>,
+}
+
+/// Whether a validator signed the block at `height`, `status` is either
+/// `signed`, `nil` (voted for no block) or `absent`.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct SignatureStatus {
+    pub height: i32,
+    pub status: String,
+}
+
+impl TryFrom<&Row> for SignatureStatus {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let height = row.try_get("height")?;
+        let block_id_flag: i32 = row.try_get("block_id_flag")?;
+
+        let status = match BlockIdFlag::try_from(block_id_flag) {
+            Ok(BlockIdFlag::Commit) => "signed",
+            Ok(BlockIdFlag::Nil) => "nil",
+            _ => "absent",
+        };
+
+        Ok(Self {
+            height,
+            status: status.to_string(),
+        })
+    }
+}
+
+/// Consecutive heights a validator did not sign.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct MissedStreak {
+    pub start_height: i32,
+    pub end_height: i32,
+    pub length: i64,
+}
+
+impl TryFrom<&Row> for MissedStreak {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let start_height = row.try_get("start_height")?;
+        let end_height = row.try_get("end_height")?;
+        let length = row.try_get("length")?;
+
+        Ok(Self {
+            start_height,
+            end_height,
+            length,
+        })
+    }
+}
+
+/// The number of heights signed and missed by a validator over a window.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorUptime {
+    /// The Namada address, null if the consensus key is unknown.
+    pub address: Option<String>,
+    /// The tendermint address of its last consensus key.
+    #[serde(with = "hex::serde")]
+    pub consensus_address: Vec<u8>,
+    pub signed_blocks: i64,
+    pub missed_blocks: i64,
+    /// `0` when the validator had no block to sign in the window.
+    pub uptime: f64,
+}
+
+impl TryFrom<&Row> for ValidatorUptime {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let address = row.try_get("validator")?;
+        let consensus_address = row.try_get("consensus_address")?;
+        let signed_blocks: i64 = row.try_get("signed")?;
+        let missed_blocks: i64 = row.try_get("missed")?;
+
+        Ok(Self {
+            address,
+            consensus_address,
+            signed_blocks,
+            missed_blocks,
+            uptime: uptime(signed_blocks, missed_blocks),
+        })
+    }
+}
+
+/// Returns the part of the blocks signed among the `signed + missed` blocks
+/// to sign, `0` if there were none.
+fn uptime(signed: i64, missed: i64) -> f64 {
+    if signed + missed == 0 {
+        return 0.0;
+    }
+
+    signed as f64 / (signed + missed) as f64
+}
+
+/// The blocks proposed by a validator over a range of heights, and the
+/// number of blocks it was expected to propose given its voting power.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProposerStats {
+    /// The Namada address, null if the consensus key is unknown.
+    pub address: Option<String>,
+    /// The tendermint address of its last consensus key.
+    #[serde(with = "hex::serde")]
+    pub consensus_address: Vec<u8>,
+    pub proposed_blocks: i64,
+    pub expected_blocks: f64,
+    /// Height of the last block it proposed in the range.
+    pub last_proposed_height: Option<i32>,
+}
+
+impl TryFrom<&Row> for ProposerStats {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let address = row.try_get("validator")?;
+        let consensus_address = row.try_get("consensus_address")?;
+        let proposed_blocks = row.try_get("proposed")?;
+        let expected_blocks = row.try_get("expected")?;
+        let last_proposed_height = row.try_get("last_height")?;
+
+        Ok(Self {
+            address,
+            consensus_address,
+            proposed_blocks,
+            expected_blocks,
+            last_proposed_height,
+        })
+    }
+}
+
+/// A block proposed by a validator.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProposedBlock {
+    #[serde(with = "hex::serde")]
+    pub block_id: Vec<u8>,
+    pub height: i32,
+    pub time: String,
+}
+
+impl TryFrom<&Row> for ProposedBlock {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let block_id = row.try_get("block_id")?;
+        let height = row.try_get("header_height")?;
+        let time = row.try_get("header_time")?;
+
+        Ok(Self {
+            block_id,
+            height,
+            time,
+        })
+    }
+}
+
+/// A slash of a validator, values the ledger did not report are null.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Slash {
+    pub validator: String,
+    pub infraction: Option<String>,
+    pub infraction_height: Option<i32>,
+    pub rate: Option<String>,
+    /// Amount in the native token smallest unit.
+    pub amount: Option<String>,
+    pub amount_denominated: Option<String>,
+    /// Height of the block the slash was processed in.
+    pub height: i32,
+    /// The block holding the evidence of the infraction, if known.
+    pub evidence_block_id: Option<String>,
+}
+
+impl TryFrom<&Row> for Slash {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let validator = row.try_get("validator")?;
+        let infraction = row.try_get("infraction")?;
+        let infraction_height = row.try_get("infraction_height")?;
+        let rate = row.try_get("rate")?;
+        let amount: Option<String> = row.try_get("amount")?;
+        let denomination = row.try_get("denomination")?;
+        let amount_denominated = amount.as_deref().and_then(|a| denominate(a, denomination));
+        let height = row.try_get("height")?;
+        let evidence_block_id = row
+            .try_get::<Option<Vec<u8>>, _>("evidence_block_id")?
+            .map(hex::encode);
+
+        Ok(Self {
+            validator,
+            infraction,
+            infraction_height,
+            rate,
+            amount,
+            amount_denominated,
+            height,
+            evidence_block_id,
+        })
+    }
+}
+
+/// A validator being jailed, or unjailed by a transaction.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Jailing {
+    pub jailed: bool,
+    pub reason: Option<String>,
+    pub height: i32,
+    /// The unjail transaction.
+    pub tx_hash: Option<String>,
+}
+
+impl TryFrom<&Row> for Jailing {
+    type Error = Error;
+
+    fn try_from(row: &Row) -> Result<Self, Self::Error> {
+        let jailed = row.try_get("jailed")?;
+        let reason = row.try_get("reason")?;
+        let height = row.try_get("height")?;
+        let tx_hash = row
+            .try_get::<Option<Vec<u8>>, _>("tx_hash")?
+            .map(hex::encode);
+
+        Ok(Self {
+            jailed,
+            reason,
+            height,
+            tx_hash,
+        })
+    }
+}
+
+/// The slashes of a validator and when it was jailed and unjailed.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ValidatorSlashes {
+    pub slashes: Vec<Slash>,
+    pub jailings: Vec<Jailing>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uptime() {
+        assert_eq!(uptime(3, 1), 0.75);
+        assert_eq!(uptime(0, 4), 0.0);
+        assert_eq!(uptime(5, 0), 1.0);
+        // an empty window
+        assert_eq!(uptime(0, 0), 0.0);
+    }
+}
@@ -0,0 +1,208 @@
+use tendermint::abci::Event;
+use tendermint_rpc::endpoint::block_results;
+
+use crate::{database::IndexedTx, decoder::TxData};
+
+// The ledger events are matched by the end of their type, so that
+// namespaced types like `pos/slash` are recognized as well.
+const SLASH_EVENT: &str = "slash";
+const JAIL_EVENT: &str = "jail";
+
+// Attributes holding each value, the first one found is used.
+const VALIDATOR_ATTRIBUTES: [&str; 3] = ["validator", "slashed-validator", "address"];
+const INFRACTION_ATTRIBUTES: [&str; 3] = ["infraction", "infraction-type", "type"];
+const INFRACTION_HEIGHT_ATTRIBUTES: [&str; 3] = ["infraction-height", "evidence-height", "height"];
+const RATE_ATTRIBUTES: [&str; 2] = ["rate", "slash-rate"];
+const AMOUNT_ATTRIBUTES: [&str; 2] = ["amount", "slashed-amount"];
+const REASON_ATTRIBUTES: [&str; 2] = ["reason", "infraction"];
+
+/// A slash of a validator, values that are not part of the event are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Slash {
+    pub validator: String,
+    pub infraction: Option<String>,
+    pub infraction_height: Option<i32>,
+    /// Decimal strings.
+    pub rate: Option<String>,
+    pub amount: Option<String>,
+}
+
+/// A validator jailed or unjailed, the unjailings come from `tx_unjail_validator`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Jailing {
+    pub validator: String,
+    pub jailed: bool,
+    /// Why the validator was jailed, e.g. the slash infraction.
+    pub reason: Option<String>,
+    pub tx_hash: Option<Vec<u8>>,
+}
+
+/// The slashes and jailings of a block.
+#[derive(Debug, Default)]
+pub(crate) struct BlockSlashes {
+    pub slashes: Vec<Slash>,
+    pub jailings: Vec<Jailing>,
+}
+
+impl BlockSlashes {
+    /// Returns the slashes and jailings found in the events of a block and the
+    /// unjailings of its transactions. Slashed validators are jailed.
+    pub fn new(block_results: &block_results::Response, txs: &[IndexedTx]) -> Self {
+        let mut slashes = Self::default();
+
+        let events = block_results
+            .begin_block_events
+            .iter()
+            .flatten()
+            .chain(block_results.end_block_events.iter().flatten())
+            .chain(block_results.finalize_block_events.iter());
+
+        for event in events {
+            let Some(validator) = attribute(event, &VALIDATOR_ATTRIBUTES) else {
+                continue;
+            };
+
+            if event.kind.ends_with(SLASH_EVENT) {
+                let infraction = attribute(event, &INFRACTION_ATTRIBUTES).map(str::to_string);
+
+                slashes.slashes.push(Slash {
+                    validator: validator.to_string(),
+                    infraction: infraction.clone(),
+                    infraction_height: attribute(event, &INFRACTION_HEIGHT_ATTRIBUTES)
+                        .and_then(|h| h.parse().ok()),
+                    rate: attribute(event, &RATE_ATTRIBUTES).and_then(decimal),
+                    amount: attribute(event, &AMOUNT_ATTRIBUTES).and_then(decimal),
+                });
+                slashes.jailings.push(Jailing {
+                    validator: validator.to_string(),
+                    jailed: true,
+                    reason: infraction,
+                    tx_hash: None,
+                });
+            } else if event.kind.ends_with(JAIL_EVENT) && !event.kind.ends_with("unjail") {
+                slashes.jailings.push(Jailing {
+                    validator: validator.to_string(),
+                    jailed: true,
+                    reason: attribute(event, &REASON_ATTRIBUTES).map(str::to_string),
+                    tx_hash: None,
+                });
+            }
+        }
+
+        for tx in txs {
+            let IndexedTx::Applied { hash, data, .. } = tx else {
+                continue;
+            };
+
+            if let TxData::UnjailValidator(validator) = data.as_ref() {
+                slashes.jailings.push(Jailing {
+                    validator: validator.to_string(),
+                    jailed: false,
+                    reason: None,
+                    tx_hash: Some(hash.clone()),
+                });
+            }
+        }
+
+        slashes
+    }
+}
+
+fn attribute<'a>(event: &'a Event, keys: &[&str]) -> Option<&'a str> {
+    keys.iter().find_map(|key| {
+        event
+            .attributes
+            .iter()
+            .find(|attr| attr.key == *key)
+            .map(|attr| attr.value.as_str())
+    })
+}
+
+// Only keep values postgres can store as NUMERIC, the block
+// can't be saved otherwise.
+fn decimal(value: &str) -> Option<String> {
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, "0"));
+
+    let is_digits = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+    (is_digits(integer) && is_digits(fraction)).then(|| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{address, block_results_with_events, ADDRESS as VALIDATOR};
+
+    #[test]
+    fn test_slashes() {
+        let block_results = block_results_with_events(vec![
+            Event::new(
+                "pos/slash",
+                [
+                    ("validator", VALIDATOR),
+                    ("infraction", "DuplicateVote"),
+                    ("infraction-height", "40"),
+                    ("rate", "0.05"),
+                    ("amount", "not a number"),
+                ],
+            ),
+            Event::new(
+                "jail",
+                [("address", "tnam1jailed"), ("reason", "missed votes")],
+            ),
+            // unjailings come from the transactions
+            Event::new("unjail", [("validator", "tnam1unjailed")]),
+            Event::new("slash", [("rate", "0.01")]),
+        ]);
+        let txs = vec![IndexedTx::applied(
+            "tx_unjail_validator",
+            &[1],
+            address(VALIDATOR),
+        )];
+
+        let slashes = BlockSlashes::new(&block_results, &txs);
+
+        assert_eq!(
+            slashes.slashes,
+            vec![Slash {
+                validator: VALIDATOR.to_string(),
+                infraction: Some("DuplicateVote".to_string()),
+                infraction_height: Some(40),
+                rate: Some("0.05".to_string()),
+                amount: None,
+            }]
+        );
+        assert_eq!(
+            slashes.jailings,
+            vec![
+                Jailing {
+                    validator: VALIDATOR.to_string(),
+                    jailed: true,
+                    reason: Some("DuplicateVote".to_string()),
+                    tx_hash: None,
+                },
+                Jailing {
+                    validator: "tnam1jailed".to_string(),
+                    jailed: true,
+                    reason: Some("missed votes".to_string()),
+                    tx_hash: None,
+                },
+                Jailing {
+                    validator: VALIDATOR.to_string(),
+                    jailed: false,
+                    reason: None,
+                    tx_hash: Some(vec![1]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(decimal("12"), Some("12".to_string()));
+        assert_eq!(decimal("0.05"), Some("0.05".to_string()));
+        assert_eq!(decimal("-1"), None);
+        assert_eq!(decimal("1."), None);
+        assert_eq!(decimal(""), None);
+    }
+}
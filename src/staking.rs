@@ -0,0 +1,282 @@
+use crate::{database::IndexedTx, decoder::TxData};
+
+/// A change of the delegations caused by an applied transaction.
+///
+/// Addresses are stored as strings and amounts in the native token
+/// smallest unit. The delegator of a bond without source is the
+/// validator itself (self-bond).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StakingChange {
+    Bond {
+        delegator: String,
+        validator: String,
+        amount: String,
+        tx_hash: Vec<u8>,
+    },
+    Unbond {
+        delegator: String,
+        validator: String,
+        amount: String,
+        tx_hash: Vec<u8>,
+    },
+    Redelegation {
+        delegator: String,
+        src_validator: String,
+        dest_validator: String,
+        amount: String,
+        tx_hash: Vec<u8>,
+    },
+    Withdraw {
+        delegator: String,
+        validator: String,
+        tx_hash: Vec<u8>,
+    },
+}
+
+impl StakingChange {
+    /// Returns the delegator, validator and signed amount of the delegations
+    /// updated by the change, a redelegation moves its amount from the source
+    /// validator to the destination one.
+    pub fn delegation_deltas(&self) -> Vec<(&str, &str, String)> {
+        match self {
+            Self::Bond {
+                delegator,
+                validator,
+                amount,
+                ..
+            } => vec![(delegator.as_str(), validator.as_str(), amount.clone())],
+            Self::Unbond {
+                delegator,
+                validator,
+                amount,
+                ..
+            } => vec![(
+                delegator.as_str(),
+                validator.as_str(),
+                format!("-{}", amount),
+            )],
+            Self::Redelegation {
+                delegator,
+                src_validator,
+                dest_validator,
+                amount,
+                ..
+            } => vec![
+                (
+                    delegator.as_str(),
+                    src_validator.as_str(),
+                    format!("-{}", amount),
+                ),
+                (delegator.as_str(), dest_validator.as_str(), amount.clone()),
+            ],
+            Self::Withdraw { .. } => vec![],
+        }
+    }
+}
+
+/// Returns the changes of the delegations caused by the transactions of a
+/// block, in the order they have been applied.
+pub(crate) fn staking_changes(txs: &[IndexedTx]) -> Vec<StakingChange> {
+    let mut changes = vec![];
+
+    for tx in txs {
+        let IndexedTx::Applied { hash, data, .. } = tx else {
+            continue;
+        };
+
+        let tx_hash = hash.clone();
+
+        match data.as_ref() {
+            TxData::Bond(bond) => {
+                changes.push(StakingChange::Bond {
+                    delegator: bond.source.as_ref().unwrap_or(&bond.validator).to_string(),
+                    validator: bond.validator.to_string(),
+                    amount: bond.amount.raw_amount().to_string(),
+                    tx_hash,
+                });
+            }
+            TxData::Unbond(unbond) => {
+                changes.push(StakingChange::Unbond {
+                    delegator: unbond
+                        .source
+                        .as_ref()
+                        .unwrap_or(&unbond.validator)
+                        .to_string(),
+                    validator: unbond.validator.to_string(),
+                    amount: unbond.amount.raw_amount().to_string(),
+                    tx_hash,
+                });
+            }
+            TxData::Redelegate(redelegation) => {
+                changes.push(StakingChange::Redelegation {
+                    delegator: redelegation.owner.to_string(),
+                    src_validator: redelegation.src_validator.to_string(),
+                    dest_validator: redelegation.dest_validator.to_string(),
+                    amount: redelegation.amount.raw_amount().to_string(),
+                    tx_hash,
+                });
+            }
+            TxData::Withdraw(withdraw) => {
+                changes.push(StakingChange::Withdraw {
+                    delegator: withdraw
+                        .source
+                        .as_ref()
+                        .unwrap_or(&withdraw.validator)
+                        .to_string(),
+                    validator: withdraw.validator.to_string(),
+                    tx_hash,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use namada_sdk::tx::data::pos::{Bond, Redelegation, Unbond, Withdraw};
+    use namada_sdk::types::token;
+
+    use super::*;
+    use crate::test_utils::{
+        address, ADDRESS as DELEGATOR, OTHER_ADDRESS as VALIDATOR, THIRD_ADDRESS as DEST_VALIDATOR,
+    };
+
+    fn redelegation(src: &str, dest: &str, amount: u64, tx_hash: u8) -> IndexedTx {
+        IndexedTx::applied(
+            "tx_redelegate",
+            &[tx_hash],
+            Redelegation {
+                src_validator: address(src),
+                dest_validator: address(dest),
+                owner: address(DELEGATOR),
+                amount: token::Amount::from_u64(amount),
+            },
+        )
+    }
+
+    #[test]
+    fn test_staking_changes() {
+        let txs = vec![
+            // a self-bond has no source
+            IndexedTx::applied(
+                "tx_bond",
+                &[1],
+                Bond {
+                    validator: address(VALIDATOR),
+                    amount: token::Amount::from_u64(100),
+                    source: None,
+                },
+            ),
+            IndexedTx::applied(
+                "tx_unbond",
+                &[2],
+                Unbond {
+                    validator: address(VALIDATOR),
+                    amount: token::Amount::from_u64(40),
+                    source: Some(address(DELEGATOR)),
+                },
+            ),
+            IndexedTx::Decrypted {
+                fee_token: None,
+                gas_used: None,
+            },
+            redelegation(VALIDATOR, DEST_VALIDATOR, 10, 3),
+            IndexedTx::applied(
+                "tx_withdraw",
+                &[4],
+                Withdraw {
+                    validator: address(VALIDATOR),
+                    source: Some(address(DELEGATOR)),
+                },
+            ),
+            // rewards claims don't change the delegations
+            IndexedTx::applied(
+                "tx_claim_rewards",
+                &[5],
+                Withdraw {
+                    validator: address(VALIDATOR),
+                    source: None,
+                },
+            ),
+        ];
+
+        assert_eq!(
+            staking_changes(&txs),
+            vec![
+                StakingChange::Bond {
+                    delegator: VALIDATOR.to_string(),
+                    validator: VALIDATOR.to_string(),
+                    amount: "100".to_string(),
+                    tx_hash: vec![1],
+                },
+                StakingChange::Unbond {
+                    delegator: DELEGATOR.to_string(),
+                    validator: VALIDATOR.to_string(),
+                    amount: "40".to_string(),
+                    tx_hash: vec![2],
+                },
+                StakingChange::Redelegation {
+                    delegator: DELEGATOR.to_string(),
+                    src_validator: VALIDATOR.to_string(),
+                    dest_validator: DEST_VALIDATOR.to_string(),
+                    amount: "10".to_string(),
+                    tx_hash: vec![3],
+                },
+                StakingChange::Withdraw {
+                    delegator: DELEGATOR.to_string(),
+                    validator: VALIDATOR.to_string(),
+                    tx_hash: vec![4],
+                },
+            ]
+        );
+    }
+    #[test]
+    fn test_redelegation_chain() {
+        // the redelegated tokens are redelegated again, back to the source
+        let txs = vec![
+            IndexedTx::applied(
+                "tx_bond",
+                &[1],
+                Bond {
+                    validator: address(VALIDATOR),
+                    amount: token::Amount::from_u64(100),
+                    source: Some(address(DELEGATOR)),
+                },
+            ),
+            redelegation(VALIDATOR, DEST_VALIDATOR, 60, 2),
+            redelegation(DEST_VALIDATOR, VALIDATOR, 20, 3),
+            IndexedTx::applied(
+                "tx_unbond",
+                &[4],
+                Unbond {
+                    validator: address(VALIDATOR),
+                    amount: token::Amount::from_u64(10),
+                    source: Some(address(DELEGATOR)),
+                },
+            ),
+        ];
+
+        let mut delegations: BTreeMap<(&str, &str), i64> = BTreeMap::new();
+        let changes = staking_changes(&txs);
+
+        for change in &changes {
+            for (delegator, validator, delta) in change.delegation_deltas() {
+                *delegations.entry((delegator, validator)).or_default() +=
+                    delta.parse::<i64>().unwrap();
+            }
+        }
+
+        assert_eq!(
+            delegations,
+            BTreeMap::from([
+                ((DELEGATOR, VALIDATOR), 50),
+                ((DELEGATOR, DEST_VALIDATOR), 40)
+            ])
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Value};
+use tendermint_rpc::endpoint::block_results;
+
+use crate::{database::IndexedTx, decoder::TxData, error::Error};
+
+// The ledger events are matched by the end of their type, like the slashes.
+const PROPOSAL_EVENT: &str = "proposal";
+
+/// A change of a PGF steward, either caused by a transaction or by the
+/// execution of a `PGFSteward` proposal.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StewardChange {
+    pub address: String,
+    /// One of `add`, `remove`, `resign` or `update_commission`.
+    pub kind: &'static str,
+    /// The split of the rewards of the steward, as a map of addresses to
+    /// decimal strings, `None` if it is unchanged.
+    pub commission: Option<Value>,
+    pub proposal_id: Option<i64>,
+    pub tx_hash: Option<Vec<u8>>,
+}
+
+impl StewardChange {
+    /// The status of the steward once the change is applied, `None` if it
+    /// is unchanged.
+    pub fn status(&self) -> Option<&'static str> {
+        steward_status(self.kind)
+    }
+}
+
+/// The status of a steward after a change of kind `kind`, `None` if it is
+/// unchanged.
+pub(crate) fn steward_status(kind: &str) -> Option<&'static str> {
+    match kind {
+        "add" => Some("active"),
+        "remove" => Some("removed"),
+        "resign" => Some("resigned"),
+        _ => None,
+    }
+}
+
+/// Returns the changes of the stewards caused by the transactions of a
+/// block, in the order they have been applied.
+pub(crate) fn steward_changes(txs: &[IndexedTx]) -> Result<Vec<StewardChange>, Error> {
+    let mut changes = vec![];
+
+    for tx in txs {
+        let IndexedTx::Applied { hash, data, .. } = tx else {
+            continue;
+        };
+
+        match data.as_ref() {
+            TxData::ResignSteward(steward) => {
+                changes.push(StewardChange {
+                    address: steward.to_string(),
+                    kind: "resign",
+                    commission: None,
+                    proposal_id: None,
+                    tx_hash: Some(hash.clone()),
+                });
+            }
+            TxData::UpdateStewardCommission(update) => {
+                let commission: BTreeMap<String, String> = update
+                    .commission
+                    .iter()
+                    .map(|(address, rate)| (address.to_string(), rate.to_string()))
+                    .collect();
+
+                changes.push(StewardChange {
+                    address: update.steward.to_string(),
+                    kind: "update_commission",
+                    commission: Some(serde_json::to_value(commission)?),
+                    proposal_id: None,
+                    tx_hash: Some(hash.clone()),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Returns the ids of the proposals that ended in a block along with
+/// whether they passed, as reported by the proposal events of the block.
+pub(crate) fn proposal_results(block_results: &block_results::Response) -> Vec<(i64, bool)> {
+    block_results
+        .end_block_events
+        .iter()
+        .flatten()
+        .chain(block_results.finalize_block_events.iter())
+        .filter(|event| event.kind.ends_with(PROPOSAL_EVENT))
+        .filter_map(|event| {
+            let attribute = |key: &str| {
+                event
+                    .attributes
+                    .iter()
+                    .find(|attr| attr.key == key)
+                    .map(|attr| attr.value.as_str())
+            };
+
+            let id = attribute("proposal_id")?.parse().ok()?;
+            let passed = attribute("tally_result")?.eq_ignore_ascii_case("passed");
+
+            Some((id, passed))
+        })
+        .collect()
+}
+
+/// Returns the ids of the proposals that passed, as reported by the
+/// proposal events of a block.
+pub(crate) fn passed_proposals(block_results: &block_results::Response) -> Vec<i64> {
+    proposal_results(block_results)
+        .into_iter()
+        .filter(|(_, passed)| *passed)
+        .map(|(id, _)| id)
+        .collect()
+}
+
+/// Returns the stewards added and removed by a passed `PGFSteward`
+/// proposal, from the type data stored along with the proposal, e.g.
+/// `{ "PGFSteward": [{ "Add": "tnam1..." }, { "Remove": "tnam1..." }] }`.
+///
+/// Like the ledger, a new steward receives all of its rewards.
+pub(crate) fn proposal_steward_changes(proposal_id: i64, kind_data: &Value) -> Vec<StewardChange> {
+    let Some(stewards) = kind_data.get("PGFSteward").and_then(Value::as_array) else {
+        return vec![];
+    };
+
+    let mut changes = vec![];
+
+    for steward in stewards {
+        let (kind, address) = match (steward.get("Add"), steward.get("Remove")) {
+            (Some(Value::String(address)), _) => ("add", address),
+            (_, Some(Value::String(address))) => ("remove", address),
+            _ => continue,
+        };
+
+        changes.push(StewardChange {
+            address: address.clone(),
+            kind,
+            commission: (kind == "add").then(|| json!({ address.as_str(): "1" })),
+            proposal_id: Some(proposal_id),
+            tx_hash: None,
+        });
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use namada_sdk::tx::data::pgf::UpdateStewardCommission;
+    use namada_sdk::types::dec::Dec;
+    use tendermint::abci::Event;
+
+    use super::*;
+    use crate::test_utils::{
+        address, block_results_with_events, ADDRESS as STEWARD, OTHER_ADDRESS as TARGET,
+    };
+
+    #[test]
+    fn test_steward_changes() {
+        let rate = Dec::new(5, 1).unwrap();
+        let txs = vec![
+            IndexedTx::applied(
+                "tx_update_steward_commission",
+                &[1],
+                UpdateStewardCommission {
+                    steward: address(STEWARD),
+                    commission: HashMap::from([(address(TARGET), rate)]),
+                },
+            ),
+            IndexedTx::Decrypted {
+                fee_token: None,
+                gas_used: None,
+            },
+            IndexedTx::applied("tx_resign_steward", &[2], address(STEWARD)),
+        ];
+
+        assert_eq!(
+            steward_changes(&txs).unwrap(),
+            vec![
+                StewardChange {
+                    address: STEWARD.to_string(),
+                    kind: "update_commission",
+                    commission: Some(json!({ TARGET: rate.to_string() })),
+                    proposal_id: None,
+                    tx_hash: Some(vec![1]),
+                },
+                StewardChange {
+                    address: STEWARD.to_string(),
+                    kind: "resign",
+                    commission: None,
+                    proposal_id: None,
+                    tx_hash: Some(vec![2]),
+                },
+            ]
+        );
+        assert_eq!(steward_status("update_commission"), None);
+        assert_eq!(steward_status("resign"), Some("resigned"));
+    }
+
+    #[test]
+    fn test_passed_proposals() {
+        let block_results = block_results_with_events(vec![
+            Event::new(
+                "governance/proposal",
+                [("proposal_id", "3"), ("tally_result", "Passed")],
+            ),
+            Event::new(
+                "governance/proposal",
+                [("proposal_id", "4"), ("tally_result", "Rejected")],
+            ),
+            Event::new(
+                "applied",
+                [("proposal_id", "5"), ("tally_result", "passed")],
+            ),
+        ]);
+
+        assert_eq!(
+            proposal_results(&block_results),
+            vec![(3, true), (4, false)]
+        );
+        assert_eq!(passed_proposals(&block_results), vec![3]);
+    }
+
+    #[test]
+    fn test_proposal_steward_changes() {
+        let kind_data = json!({
+            "PGFSteward": [{ "Add": "tnam1add" }, { "Remove": "tnam1remove" }, { "Other": "x" }]
+        });
+
+        let changes = proposal_steward_changes(7, &kind_data);
+
+        assert_eq!(changes.len(), 2);
+
+        assert_eq!(changes[0].address, "tnam1add");
+        assert_eq!(changes[0].status(), Some("active"));
+        assert_eq!(changes[0].commission, Some(json!({ "tnam1add": "1" })));
+        assert_eq!(changes[0].proposal_id, Some(7));
+
+        assert_eq!(changes[1].address, "tnam1remove");
+        assert_eq!(changes[1].status(), Some("removed"));
+        assert_eq!(changes[1].commission, None);
+
+        assert!(proposal_steward_changes(7, &json!("Default")).is_empty());
+    }
+}
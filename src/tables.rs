@@ -1,3 +1,13 @@
+pub fn get_create_schema_migrations_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.schema_migrations (
+        name TEXT PRIMARY KEY,
+        applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+    );",
+        network
+    )
+}
+
 pub fn get_create_block_table_query(network: &str) -> String {
     format!(
         "CREATE TABLE IF NOT EXISTS {}.blocks (
@@ -23,7 +33,8 @@ pub fn get_create_block_table_query(network: &str) -> String {
         commit_round INTEGER,
         commit_block_id_hash BYTEA,
         commit_block_id_parts_header_total INTEGER,
-        commit_block_id_parts_header_hash BYTEA
+        commit_block_id_parts_header_hash BYTEA,
+        epoch INTEGER
     );",
         network
     )
@@ -43,7 +54,462 @@ pub fn get_create_transactions_table_query(network: &str) -> String {
         code_type TEXT,
         memo BYTEA,
         data JSON,
-        return_code INTEGER
+        return_code INTEGER,
+        gas_used BIGINT
+    );",
+        network
+    )
+}
+
+pub fn get_create_tx_sections_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.tx_sections (
+        tx_hash BYTEA NOT NULL,
+        block_id BYTEA NOT NULL,
+        section_index INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        hash BYTEA NOT NULL,
+        size INTEGER NOT NULL,
+        signer_address TEXT,
+        signer_public_keys TEXT[],
+        signature_targets BYTEA[]
+    );",
+        network
+    )
+}
+
+pub fn get_create_raw_transactions_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.raw_transactions (
+        hash BYTEA NOT NULL,
+        block_id BYTEA NOT NULL,
+        tx_index INTEGER,
+        compression TEXT NOT NULL,
+        raw BYTEA NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_raw_block_results_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.raw_block_results (
+        block_id BYTEA PRIMARY KEY,
+        height INTEGER NOT NULL,
+        results JSONB NOT NULL,
+        state JSONB NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_decode_failures_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.decode_failures (
+        tx_hash BYTEA NOT NULL,
+        block_id BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        code_type TEXT,
+        error TEXT NOT NULL,
+        payload BYTEA NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_balance_changes_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.balance_changes (
+        id BIGSERIAL PRIMARY KEY,
+        address TEXT NOT NULL,
+        token TEXT NOT NULL,
+        delta NUMERIC NOT NULL,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA,
+        kind TEXT NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_delegations_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.delegations (
+        delegator TEXT NOT NULL,
+        validator TEXT NOT NULL,
+        amount NUMERIC NOT NULL,
+        height INTEGER NOT NULL,
+        PRIMARY KEY (delegator, validator)
+    );",
+        network
+    )
+}
+
+pub fn get_create_unbonds_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.unbonds (
+        delegator TEXT NOT NULL,
+        validator TEXT NOT NULL,
+        amount NUMERIC NOT NULL,
+        epoch INTEGER,
+        withdrawable_epoch INTEGER,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL,
+        withdraw_height INTEGER,
+        withdraw_tx_hash BYTEA
+    );",
+        network
+    )
+}
+
+pub fn get_create_redelegations_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.redelegations (
+        delegator TEXT NOT NULL,
+        src_validator TEXT NOT NULL,
+        dest_validator TEXT NOT NULL,
+        amount NUMERIC NOT NULL,
+        epoch INTEGER,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_proposals_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.proposals (
+        id BIGINT PRIMARY KEY,
+        author TEXT NOT NULL,
+        content_hash BYTEA NOT NULL,
+        content JSONB,
+        kind TEXT NOT NULL,
+        kind_data JSONB,
+        voting_start_epoch INTEGER NOT NULL,
+        voting_end_epoch INTEGER NOT NULL,
+        grace_epoch INTEGER NOT NULL,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_proposal_votes_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.proposal_votes (
+        proposal_id BIGINT NOT NULL,
+        voter TEXT NOT NULL,
+        vote TEXT NOT NULL,
+        is_validator BOOLEAN NOT NULL,
+        voting_power NUMERIC,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL,
+        PRIMARY KEY (proposal_id, voter)
+    );",
+        network
+    )
+}
+
+pub fn get_create_validators_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validators (
+        address TEXT PRIMARY KEY,
+        consensus_key TEXT,
+        commission_rate NUMERIC,
+        max_commission_rate_change NUMERIC,
+        email TEXT,
+        description TEXT,
+        website TEXT,
+        discord_handle TEXT,
+        avatar TEXT,
+        state TEXT NOT NULL DEFAULT 'active',
+        height INTEGER,
+        last_change_height INTEGER NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_validator_changes_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validator_changes (
+        id BIGSERIAL PRIMARY KEY,
+        address TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        data JSONB,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_validator_consensus_keys_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validator_consensus_keys (
+        consensus_address BYTEA NOT NULL,
+        consensus_key TEXT NOT NULL,
+        validator TEXT NOT NULL,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA
+    );",
+        network
+    )
+}
+
+pub fn get_create_validator_sets_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validator_sets (
+        height INTEGER NOT NULL,
+        validators_hash BYTEA NOT NULL,
+        consensus_address BYTEA NOT NULL,
+        voting_power BIGINT NOT NULL,
+        PRIMARY KEY (height, consensus_address)
+    );",
+        network
+    )
+}
+
+pub fn get_create_validator_signatures_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validator_signatures (
+        consensus_address BYTEA NOT NULL,
+        height INTEGER NOT NULL,
+        block_id_flag INTEGER NOT NULL,
+        signed_total BIGINT NOT NULL,
+        missed_total BIGINT NOT NULL,
+        PRIMARY KEY (consensus_address, height)
+    );",
+        network
+    )
+}
+
+pub fn get_create_validator_signature_counters_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.validator_signature_counters (
+        consensus_address BYTEA PRIMARY KEY,
+        signed BIGINT NOT NULL,
+        missed BIGINT NOT NULL,
+        height INTEGER NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_slashes_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.slashes (
+        id BIGSERIAL PRIMARY KEY,
+        validator TEXT NOT NULL,
+        infraction TEXT,
+        infraction_height INTEGER,
+        rate NUMERIC,
+        amount NUMERIC,
+        height INTEGER NOT NULL,
+        evidence_block_id BYTEA
+    );",
+        network
+    )
+}
+
+pub fn get_create_jailings_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.jailings (
+        id BIGSERIAL PRIMARY KEY,
+        validator TEXT NOT NULL,
+        jailed BOOLEAN NOT NULL,
+        reason TEXT,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA
+    );",
+        network
+    )
+}
+
+pub fn get_create_accounts_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.accounts (
+        address TEXT PRIMARY KEY,
+        vp_code_hash BYTEA,
+        threshold INTEGER,
+        public_keys TEXT[] NOT NULL DEFAULT ARRAY[]::TEXT[],
+        height INTEGER NOT NULL,
+        last_update_height INTEGER NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_account_updates_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.account_updates (
+        id BIGSERIAL PRIMARY KEY,
+        address TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        vp_code_hash BYTEA,
+        threshold INTEGER,
+        public_keys TEXT[],
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_revealed_public_keys_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.revealed_public_keys (
+        public_key TEXT PRIMARY KEY,
+        address TEXT NOT NULL,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_tokens_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.tokens (
+        address TEXT PRIMARY KEY,
+        alias TEXT,
+        denomination INTEGER,
+        is_native BOOLEAN NOT NULL DEFAULT FALSE
+    );",
+        network
+    )
+}
+
+pub fn get_create_shielded_pool_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.shielded_pool (
+        token TEXT NOT NULL,
+        height INTEGER NOT NULL,
+        delta NUMERIC NOT NULL,
+        balance NUMERIC NOT NULL,
+        PRIMARY KEY (token, height)
+    );",
+        network
+    )
+}
+
+pub fn get_create_block_fees_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.block_fees (
+        height INTEGER NOT NULL,
+        epoch INTEGER,
+        token TEXT NOT NULL,
+        tx_count INTEGER NOT NULL,
+        total_fee NUMERIC NOT NULL,
+        gas_limit BIGINT NOT NULL,
+        gas_used BIGINT NOT NULL DEFAULT 0,
+        fees_per_gas NUMERIC[] NOT NULL,
+        median_fee_per_gas NUMERIC NOT NULL,
+        PRIMARY KEY (height, token)
+    );",
+        network
+    )
+}
+
+pub fn get_create_epoch_fees_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.epoch_fees (
+        epoch INTEGER NOT NULL,
+        token TEXT NOT NULL,
+        tx_count INTEGER NOT NULL,
+        total_fee NUMERIC NOT NULL,
+        gas_limit BIGINT NOT NULL,
+        gas_used BIGINT NOT NULL,
+        median_fee_per_gas NUMERIC NOT NULL,
+        start_height INTEGER NOT NULL,
+        end_height INTEGER NOT NULL,
+        PRIMARY KEY (epoch, token)
+    );",
+        network
+    )
+}
+
+/// Statistics of the blocks of every `interval`, either `hour` or `day`.
+pub fn get_create_stats_table_query(network: &str, interval: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.stats_{} (
+        bucket TIMESTAMP PRIMARY KEY,
+        start_height INTEGER NOT NULL,
+        end_height INTEGER NOT NULL,
+        num_blocks INTEGER NOT NULL,
+        num_txs INTEGER NOT NULL,
+        num_success INTEGER NOT NULL,
+        txs_by_code_type JSONB NOT NULL,
+        unique_signers INTEGER NOT NULL,
+        avg_block_interval DOUBLE PRECISION
+    );",
+        network, interval
+    )
+}
+
+pub fn get_create_bridge_pool_transfers_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.bridge_pool_transfers (
+        id BIGSERIAL PRIMARY KEY,
+        transfer_hash BYTEA NOT NULL,
+        kind TEXT NOT NULL,
+        asset TEXT NOT NULL,
+        token TEXT,
+        recipient TEXT NOT NULL,
+        sender TEXT NOT NULL,
+        amount NUMERIC NOT NULL,
+        gas_fee NUMERIC NOT NULL,
+        gas_fee_token TEXT NOT NULL,
+        gas_fee_payer TEXT NOT NULL,
+        status TEXT NOT NULL DEFAULT 'pending',
+        height INTEGER NOT NULL,
+        status_height INTEGER,
+        tx_hash BYTEA NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_stewards_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.stewards (
+        address TEXT PRIMARY KEY,
+        status TEXT NOT NULL,
+        commission JSONB,
+        height INTEGER NOT NULL,
+        last_change_height INTEGER NOT NULL
+    );",
+        network
+    )
+}
+
+pub fn get_create_steward_changes_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.steward_changes (
+        id BIGSERIAL PRIMARY KEY,
+        address TEXT NOT NULL,
+        kind TEXT NOT NULL,
+        commission JSONB,
+        proposal_id BIGINT,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA
+    );",
+        network
+    )
+}
+
+pub fn get_create_rewards_table_query(network: &str) -> String {
+    format!(
+        "CREATE TABLE IF NOT EXISTS {}.rewards (
+        id BIGSERIAL PRIMARY KEY,
+        delegator TEXT NOT NULL,
+        validator TEXT NOT NULL,
+        amount NUMERIC,
+        epoch INTEGER,
+        height INTEGER NOT NULL,
+        tx_hash BYTEA NOT NULL
     );",
         network
     )
@@ -53,7 +519,9 @@ pub fn get_create_evidences_table_query(network: &str) -> String {
     format!(
         "CREATE TABLE IF NOT EXISTS {}.evidences (
         block_id BYTEA NOT NULL,
+        kind TEXT NOT NULL DEFAULT 'duplicate_vote',
         height INTEGER,
+        conflicting_height INTEGER,
         time TEXT,
         address BYTEA,
         total_voting_power TEXT NOT NULL,
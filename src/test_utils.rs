@@ -0,0 +1,33 @@
+use namada_sdk::types::address::Address;
+use tendermint::abci::Event;
+use tendermint_rpc::endpoint::block_results;
+
+// Established addresses, whose role (validator, delegator, transfer source or
+// target, ...) depends on the test.
+pub(crate) const ADDRESS: &str = "tnam1qqqszqgpqyqszqgpqyqszqgpqyqszqgpqyr40qkp";
+pub(crate) const OTHER_ADDRESS: &str = "tnam1qqpqyqszqgpqyqszqgpqyqszqgpqyqszqgafm3ew";
+pub(crate) const THIRD_ADDRESS: &str = "tnam1qqzqgpqyqszqgpqyqszqgpqyqszqgpqyqsp0cu9c";
+// An implicit address, e.g. the one of an account.
+pub(crate) const IMPLICIT_ADDRESS: &str = "tnam1qyqszqgpqyqszqgpqyqszqgpqyqszqgpqyxkp9ty";
+// The address of the native token.
+pub(crate) const NATIVE: &str = "tnam1qqpsxqcrqvpsxqcrqvpsxqcrqvpsxqcrqvva634y";
+
+/// Returns the address encoded as `address`.
+pub(crate) fn address(address: &str) -> Address {
+    Address::decode(address).unwrap()
+}
+
+/// Returns block results whose only events are `events`, as end block events.
+pub(crate) fn block_results_with_events(events: Vec<Event>) -> block_results::Response {
+    serde_json::from_value(serde_json::json!({
+        "height": "1",
+        "txs_results": null,
+        "begin_block_events": null,
+        "end_block_events": events,
+        "finalize_block_events": [],
+        "validator_updates": [],
+        "consensus_param_updates": null,
+        "app_hash": "",
+    }))
+    .expect("Invalid block results")
+}
@@ -1,6 +1,11 @@
+use crate::RawTxCompression;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use namada_sdk::tx::data::TxType;
 use std::collections::HashMap;
+use std::io::{Read, Write};
 use std::{env, fs};
+use tendermint::abci::Event;
+use tendermint_rpc::endpoint::block_results;
 
 const CHECKSUMS_FILE_PATH_ENV: &str = "CHECKSUMS_FILE_PATH";
 const CHECKSUMS_REMOTE_URL_ENV: &str = "CHECKSUMS_REMOTE_URL";
@@ -46,3 +51,63 @@ pub fn load_checksums() -> Result<HashMap<String, String>, crate::Error> {
 
     Ok(checksums_map)
 }
+
+/// Compress the raw bytes of a transaction before storing them.
+pub fn compress_raw_tx(compression: RawTxCompression, raw: &[u8]) -> Result<Vec<u8>, crate::Error> {
+    match compression {
+        RawTxCompression::None => Ok(raw.to_vec()),
+        RawTxCompression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(raw)?;
+            Ok(encoder.finish()?)
+        }
+    }
+}
+
+/// Returns the raw bytes of a transaction stored using `compression`.
+pub fn decompress_raw_tx(
+    compression: RawTxCompression,
+    stored: &[u8],
+) -> Result<Vec<u8>, crate::Error> {
+    match compression {
+        RawTxCompression::None => Ok(stored.to_vec()),
+        RawTxCompression::Gzip => {
+            let mut raw = Vec::new();
+            GzDecoder::new(stored).read_to_end(&mut raw)?;
+            Ok(raw)
+        }
+    }
+}
+
+/// Returns the end block event of the transaction whose hash is `hash`, as a
+/// lowercase hex string.
+pub(crate) fn tx_event<'a>(
+    block_results: &'a block_results::Response,
+    hash: &str,
+) -> Option<&'a Event> {
+    block_results
+        .end_block_events
+        .iter()
+        .flatten()
+        .find(|event| {
+            event
+                .attributes
+                .iter()
+                .any(|attr| attr.key == "hash" && attr.value.to_ascii_lowercase() == hash)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw_tx_compression_roundtrip() {
+        let raw: Vec<u8> = (0..4096).map(|i| (i % 7) as u8).collect();
+
+        for compression in [RawTxCompression::None, RawTxCompression::Gzip] {
+            let stored = compress_raw_tx(compression, &raw).unwrap();
+            assert_eq!(decompress_raw_tx(compression, &stored).unwrap(), raw);
+        }
+    }
+}
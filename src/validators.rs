@@ -0,0 +1,357 @@
+use namada_sdk::types::address::Address;
+use namada_sdk::types::key::{common::PublicKey, tm_consensus_key_raw_hash};
+use serde_json::Value;
+
+use crate::{database::IndexedTx, decoder::TxData, error::Error};
+
+/// The metadata of a validator, a `None` field is left unchanged and an
+/// empty string removes it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct ValidatorMetadata {
+    pub email: Option<String>,
+    pub description: Option<String>,
+    pub website: Option<String>,
+    pub discord_handle: Option<String>,
+    pub avatar: Option<String>,
+}
+
+/// A change of a validator caused by an applied transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ValidatorUpdate {
+    /// A new validator, rates are decimal strings.
+    Create {
+        consensus_key: String,
+        commission_rate: String,
+        max_commission_rate_change: String,
+        metadata: ValidatorMetadata,
+    },
+    /// A change of some fields of the validator, `None` fields are left unchanged.
+    Update {
+        consensus_key: Option<String>,
+        commission_rate: Option<String>,
+        metadata: ValidatorMetadata,
+        /// The new state, e.g. `inactive`.
+        state: Option<&'static str>,
+    },
+}
+
+/// A consensus key and its tendermint address, which identifies the
+/// validator in the block commits.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ConsensusKey {
+    pub key: String,
+    pub address: Vec<u8>,
+}
+
+impl TryFrom<&PublicKey> for ConsensusKey {
+    type Error = Error;
+
+    fn try_from(key: &PublicKey) -> Result<Self, Self::Error> {
+        Ok(Self {
+            key: key.to_string(),
+            address: hex::decode(tm_consensus_key_raw_hash(key))?,
+        })
+    }
+}
+
+/// Returns the tendermint address, consensus key and Namada address of the
+/// `validator` of a validator set, read by its `consensus_address`, unless its
+/// current consensus `key` is another one, e.g. already replaced by a pending
+/// key change.
+pub(crate) fn validator_set_key(
+    consensus_address: &[u8],
+    validator: &Address,
+    key: &PublicKey,
+) -> Result<Option<(Vec<u8>, String, String)>, Error> {
+    let consensus_key = ConsensusKey::try_from(key)?;
+
+    if consensus_key.address != consensus_address {
+        return Ok(None);
+    }
+
+    Ok(Some((
+        consensus_key.address,
+        consensus_key.key,
+        validator.to_string(),
+    )))
+}
+
+/// A change of the validator `address`, along with the transaction data
+/// kept in its history.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ValidatorChange {
+    pub address: String,
+    /// The transaction type without its `tx_` prefix, e.g. `become_validator`.
+    pub kind: String,
+    pub update: ValidatorUpdate,
+    /// The consensus key set by the change if any.
+    pub consensus_key: Option<ConsensusKey>,
+    pub data: Value,
+    pub tx_hash: Vec<u8>,
+}
+
+/// Returns the changes of the validators caused by the transactions of a
+/// block, in the order they have been applied.
+pub(crate) fn validator_changes(txs: &[IndexedTx]) -> Result<Vec<ValidatorChange>, Error> {
+    let mut changes = vec![];
+
+    for tx in txs {
+        let IndexedTx::Applied {
+            hash,
+            code_type,
+            data,
+            ..
+        } = tx
+        else {
+            continue;
+        };
+
+        changes.extend(validator_change(hash, code_type, data)?);
+    }
+
+    Ok(changes)
+}
+
+/// Returns the change of a validator caused by an applied transaction of
+/// type `code_type`, `None` if it doesn't change any validator.
+pub(crate) fn validator_change(
+    hash: &[u8],
+    code_type: &str,
+    tx_data: &TxData,
+) -> Result<Option<ValidatorChange>, Error> {
+    let mut consensus_key = None;
+
+    let (address, update, data) = match tx_data {
+        TxData::BecomeValidator(become_validator) => {
+            consensus_key = Some(ConsensusKey::try_from(&become_validator.consensus_key)?);
+            let update = ValidatorUpdate::Create {
+                consensus_key: become_validator.consensus_key.to_string(),
+                commission_rate: become_validator.commission_rate.to_string(),
+                max_commission_rate_change: become_validator.max_commission_rate_change.to_string(),
+                metadata: ValidatorMetadata {
+                    email: Some(become_validator.email.clone()),
+                    description: become_validator.description.clone(),
+                    website: become_validator.website.clone(),
+                    discord_handle: become_validator.discord_handle.clone(),
+                    avatar: become_validator.avatar.clone(),
+                },
+            };
+
+            (
+                become_validator.address.clone(),
+                update,
+                serde_json::to_value(become_validator)?,
+            )
+        }
+        TxData::ChangeValidatorMetadata(change) => {
+            let update = ValidatorUpdate::Update {
+                consensus_key: None,
+                commission_rate: change.commission_rate.as_ref().map(|r| r.to_string()),
+                metadata: ValidatorMetadata {
+                    email: change.email.clone(),
+                    description: change.description.clone(),
+                    website: change.website.clone(),
+                    discord_handle: change.discord_handle.clone(),
+                    avatar: change.avatar.clone(),
+                },
+                state: None,
+            };
+
+            (
+                change.validator.clone(),
+                update,
+                serde_json::to_value(change)?,
+            )
+        }
+        TxData::ChangeValidatorCommission(change) => {
+            let update = ValidatorUpdate::Update {
+                consensus_key: None,
+                commission_rate: Some(change.new_rate.to_string()),
+                metadata: Default::default(),
+                state: None,
+            };
+
+            (
+                change.validator.clone(),
+                update,
+                serde_json::to_value(change)?,
+            )
+        }
+        TxData::ChangeConsensusKey(change) => {
+            consensus_key = Some(ConsensusKey::try_from(&change.consensus_key)?);
+            let update = ValidatorUpdate::Update {
+                consensus_key: Some(change.consensus_key.to_string()),
+                commission_rate: None,
+                metadata: Default::default(),
+                state: None,
+            };
+
+            (
+                change.validator.clone(),
+                update,
+                serde_json::to_value(change)?,
+            )
+        }
+        TxData::DeactivateValidator(address)
+        | TxData::ReactivateValidator(address)
+        | TxData::UnjailValidator(address) => {
+            let state = if matches!(tx_data, TxData::DeactivateValidator(_)) {
+                "inactive"
+            } else {
+                "active"
+            };
+
+            let update = ValidatorUpdate::Update {
+                consensus_key: None,
+                commission_rate: None,
+                metadata: Default::default(),
+                state: Some(state),
+            };
+
+            (address.clone(), update, serde_json::to_value(address)?)
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(ValidatorChange {
+        address: address.to_string(),
+        kind: code_type.trim_start_matches("tx_").to_string(),
+        update,
+        consensus_key,
+        data,
+        tx_hash: hash.to_vec(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use namada_sdk::tx::data::pos::{CommissionChange, ConsensusKeyChange};
+    use namada_sdk::types::dec::Dec;
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_utils::{address, ADDRESS as VALIDATOR};
+
+    // the ed25519 base point
+    const CONSENSUS_KEY: &str =
+        "tpknam1qpvxvenxvenxvenxvenxvenxvenxvenxvenxvenxvenxvenxvenxv5rt7eh";
+
+    #[test]
+    fn test_consensus_key() {
+        let key = PublicKey::from_str(CONSENSUS_KEY).unwrap();
+
+        assert_eq!(
+            ConsensusKey::try_from(&key).unwrap(),
+            ConsensusKey {
+                key: CONSENSUS_KEY.to_string(),
+                address: hex::decode("CB05C9FAC26332F9ABC5F4F50B47E39EDAB18BD5").unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_validator_set_key() {
+        let key = PublicKey::from_str(CONSENSUS_KEY).unwrap();
+        let consensus_address = hex::decode("CB05C9FAC26332F9ABC5F4F50B47E39EDAB18BD5").unwrap();
+
+        assert_eq!(
+            validator_set_key(&consensus_address, &address(VALIDATOR), &key).unwrap(),
+            Some((
+                consensus_address,
+                CONSENSUS_KEY.to_string(),
+                VALIDATOR.to_string()
+            ))
+        );
+
+        // the key of the set was replaced
+        assert_eq!(
+            validator_set_key(&[0; 20], &address(VALIDATOR), &key).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_validator_changes() {
+        let rate = Dec::new(5, 2).unwrap();
+        let txs = vec![
+            IndexedTx::applied(
+                "tx_change_consensus_key",
+                &[1],
+                ConsensusKeyChange {
+                    validator: address(VALIDATOR),
+                    consensus_key: PublicKey::from_str(CONSENSUS_KEY).unwrap(),
+                },
+            ),
+            IndexedTx::Decrypted {
+                fee_token: None,
+                gas_used: None,
+            },
+            IndexedTx::applied(
+                "tx_change_validator_commission",
+                &[2],
+                CommissionChange {
+                    validator: address(VALIDATOR),
+                    new_rate: rate,
+                },
+            ),
+            IndexedTx::applied("tx_deactivate_validator", &[3], address(VALIDATOR)),
+            IndexedTx::applied("tx_unjail_validator", &[4], address(VALIDATOR)),
+        ];
+
+        let changes = validator_changes(&txs).unwrap();
+
+        assert_eq!(
+            changes
+                .iter()
+                .map(|c| (c.address.as_str(), c.kind.as_str(), c.tx_hash.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (VALIDATOR, "change_consensus_key", vec![1]),
+                (VALIDATOR, "change_validator_commission", vec![2]),
+                (VALIDATOR, "deactivate_validator", vec![3]),
+                (VALIDATOR, "unjail_validator", vec![4]),
+            ]
+        );
+
+        assert_eq!(
+            changes[0].update,
+            ValidatorUpdate::Update {
+                consensus_key: Some(CONSENSUS_KEY.to_string()),
+                commission_rate: None,
+                metadata: Default::default(),
+                state: None,
+            }
+        );
+        assert_eq!(
+            changes[0].consensus_key.as_ref().map(|k| k.key.as_str()),
+            Some(CONSENSUS_KEY)
+        );
+        assert_eq!(
+            changes[1].update,
+            ValidatorUpdate::Update {
+                consensus_key: None,
+                commission_rate: Some(rate.to_string()),
+                metadata: Default::default(),
+                state: None,
+            }
+        );
+        assert!(changes[1].consensus_key.is_none());
+        assert!(matches!(
+            changes[2].update,
+            ValidatorUpdate::Update {
+                state: Some("inactive"),
+                ..
+            }
+        ));
+        assert!(matches!(
+            changes[3].update,
+            ValidatorUpdate::Update {
+                state: Some("active"),
+                ..
+            }
+        ));
+        assert_eq!(changes[3].data, json!(VALIDATOR));
+    }
+}
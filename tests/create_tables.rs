@@ -0,0 +1,80 @@
+mod utils;
+
+#[cfg(test)]
+mod create_tables {
+    use sqlx::Row;
+
+    use crate::utils::{create_test_db, destroy_test_db, helper_db};
+
+    const SCHEMA_DB_NAME: &str = "testingdb_schema";
+
+    const TABLES: [&str; 35] = [
+        "blocks",
+        "commit_signatures",
+        "transactions",
+        "evidences",
+        "tx_sections",
+        "raw_transactions",
+        "decode_failures",
+        "balance_changes",
+        "shielded_pool",
+        "block_fees",
+        "epoch_fees",
+        "rewards",
+        "stewards",
+        "steward_changes",
+        "bridge_pool_transfers",
+        "stats_hour",
+        "stats_day",
+        "delegations",
+        "unbonds",
+        "redelegations",
+        "proposals",
+        "proposal_votes",
+        "validators",
+        "validator_changes",
+        "validator_consensus_keys",
+        "validator_sets",
+        "validator_signatures",
+        "validator_signature_counters",
+        "slashes",
+        "jailings",
+        "accounts",
+        "account_updates",
+        "tokens",
+        "revealed_public_keys",
+        "tx_transfer",
+    ];
+
+    #[tokio::test]
+    async fn create_tables() {
+        let helper_db = helper_db().await;
+
+        destroy_test_db(helper_db.pool(), SCHEMA_DB_NAME).await;
+
+        let db = create_test_db(helper_db.pool(), SCHEMA_DB_NAME).await;
+
+        db.create_tables().await.unwrap();
+        // creating the tables again must leave them untouched
+        db.create_tables().await.unwrap();
+
+        let rows = sqlx::query(
+            "SELECT table_name::TEXT FROM information_schema.tables WHERE table_schema = 'testnet'",
+        )
+        .fetch_all(db.pool())
+        .await
+        .unwrap();
+
+        let tables: Vec<String> = rows
+            .iter()
+            .map(|row| row.try_get("table_name").unwrap())
+            .collect();
+
+        for table in TABLES {
+            assert!(tables.iter().any(|t| t == table), "missing table {table}");
+        }
+
+        db.pool().close().await;
+        destroy_test_db(helper_db.pool(), SCHEMA_DB_NAME).await;
+    }
+}